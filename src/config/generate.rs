@@ -15,22 +15,32 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
+use std::ffi::{OsStr, OsString};
 use std::fs::canonicalize;
 use std::ops::Index;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use clap::OsValues;
 use rayon::prelude::*;
 
-use clap::{crate_name, crate_version, Arg, ArgMatches};
+use clap::{crate_name, crate_version, Arg, ArgGroup, ArgMatches};
 use indicatif::ProgressBar;
 use time::UtcOffset;
 
 use crate::config::install_hot_keys::install_hot_keys;
 use crate::data::filesystem_info::FilesystemInfo;
 use crate::data::paths::PathData;
+use crate::display_versions::template::FormatTemplate;
+use crate::exec::ounce::Ounce;
+use crate::exec::snapshot::SnapNameTemplate;
+use crate::library::audit::{AuditLog, DEFAULT_AUDIT_LOG};
+use crate::library::hooks::Hooks;
 use crate::library::results::{HttmError, HttmResult};
-use crate::library::utility::{read_stdin, HttmIsDir};
+use crate::library::utility::{
+    glob_to_regex, parse_date, parse_duration, parse_size, read_stdin,
+    strftime_to_format_description, DateFormat, HttmIsDir,
+};
 use crate::ROOT_DIRECTORY;
 
 #[derive(Debug, Clone)]
@@ -38,12 +48,40 @@ pub enum ExecMode {
     Interactive(InteractiveMode),
     NonInteractiveRecursive(indicatif::ProgressBar),
     Display,
-    SnapFileMount(String),
-    Purge(Option<ListSnapsFilters>),
+    SnapFileMount(SnapshotOptions),
+    Purge(Option<ListSnapsFilters>, PurgeConsent),
     MountsForFiles(MountDisplay),
     SnapsForFiles(Option<ListSnapsFilters>),
     NumVersions(NumVersionsMode),
     RollForward(String),
+    Diff(DiffMode),
+    Changelog,
+    ExportGit(PathBuf),
+    Ounce(OunceOptions),
+    Watch(WatchOptions),
+    Prune(PruneOptions),
+    Hold(Option<ListSnapsFilters>, HoldAction),
+    RecoverDeleted(RecoverOptions),
+    DirSummary,
+    TreeDiff(TreeDiffOptions),
+    Grep(regex::Regex),
+    Find(FindOptions),
+}
+
+impl ExecMode {
+    fn runs_hooks(&self) -> bool {
+        matches!(
+            self,
+            ExecMode::Interactive(InteractiveMode::Restore(_))
+                | ExecMode::SnapFileMount(_)
+                | ExecMode::Purge(_, _)
+                | ExecMode::RollForward(_)
+                | ExecMode::Ounce(_)
+                | ExecMode::Watch(_)
+                | ExecMode::Prune(_)
+                | ExecMode::RecoverDeleted(_)
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -81,6 +119,18 @@ pub enum PrintMode {
     RawZero,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffMode {
+    Unified,
+    SideBySide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelimitedFormat {
+    Csv,
+    Tsv,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DeletedMode {
     DepthOfOne,
@@ -93,6 +143,116 @@ pub enum ListSnapsOfType {
     All,
     UniqueMetadata,
     UniqueContents,
+    UniqueSnapTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    pub suffix: String,
+    pub template: SnapNameTemplate,
+    // also snapshot all descendent datasets, as with "zfs snapshot -r"
+    pub recursive: bool,
+    // user properties, like "com.example:reason=upgrade", set upon each new snapshot
+    pub properties: Vec<String>,
+}
+
+impl SnapshotOptions {
+    pub fn from_suffix(suffix: &str) -> Self {
+        Self {
+            suffix: suffix.to_owned(),
+            template: SnapNameTemplate::default(),
+            recursive: false,
+            properties: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TreeDiffOptions {
+    pub dir: PathBuf,
+    // snapshot names, as given by the user
+    pub snap_a: String,
+    // None is the live version
+    pub opt_snap_b: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FindOptions {
+    // matched against an entry's file name or, if the glob contains a "/",
+    // against its path relative to its dataset's mount
+    pub regex: regex::Regex,
+    pub match_relative_path: bool,
+    // a dataset's mount or name, like "/home" or "rpool/home"
+    pub opt_dataset: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OunceOptions {
+    // the program to execute, followed by its arguments
+    pub command: Vec<OsString>,
+    pub suffix: String,
+    pub background: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub suffix: String,
+    // how long a dataset must be without changes before we snapshot it
+    pub debounce: Duration,
+    // the minimum time between snapshots of the same dataset
+    pub rate_limit: Duration,
+    pub max_per_hour: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HoldAction {
+    Hold(String),
+    Release(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurgeConsent {
+    Interactive,
+    AssumeYes,
+    DryRun,
+}
+
+#[derive(Debug, Clone)]
+pub struct PruneOptions {
+    // the number of most recent hours, days, and weeks for which to keep a snapshot
+    pub keep_hourly: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub opt_suffix: Option<String>,
+    pub consent: PurgeConsent,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecoverOptions {
+    // only recover entries which may have been deleted at or after this time
+    pub opt_since: Option<SystemTime>,
+    // recover to this directory, instead of to each entry's original location
+    pub opt_dest: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSort {
+    ModifyTime,
+    Size,
+    SnapshotDate,
+    SnapshotName,
+}
+
+// applied to snapshot versions after uniqueness processing
+#[derive(Debug, Clone)]
+pub struct VersionFilters {
+    pub opt_since: Option<SystemTime>,
+    pub opt_until: Option<SystemTime>,
+    pub opt_min_size: Option<u64>,
+    pub opt_max_size: Option<u64>,
+    pub opt_sort: Option<VersionSort>,
+    pub reverse: bool,
+    pub opt_limit: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +260,7 @@ pub struct ListSnapsFilters {
     pub select_mode: bool,
     pub omit_num_snaps: usize,
     pub name_filters: Option<Vec<String>>,
+    pub with_space: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -211,6 +372,7 @@ fn parse_args() -> ArgMatches {
                 .long("preview")
                 .help("user may specify a command to preview snapshots while in select view.  This argument optionally takes a value specifying the command to be executed.  \
                 The default value/command, if no command value specified, is a 'bowie' formatted 'diff'.  \
+                The value \"diff\" selects httm's built-in unified diff of the snapshot and live file, which requires no external programs.  \
                 User defined commands must specify the snapshot file name \"{snap_file}\" and the live file name \"{live_file}\" within their shell command.")
                 .takes_value(true)
                 .min_values(0)
@@ -224,7 +386,7 @@ fn parse_args() -> ArgMatches {
                 .visible_aliases(&["unique"])
                 .takes_value(true)
                 .default_missing_value("contents")
-                .possible_values(["all", "no-filter", "metadata", "contents"])
+                .possible_values(["all", "no-filter", "metadata", "contents", "snap-time"])
                 .min_values(0)
                 .require_equals(true)
                 .help("comparing file versions solely on the basis of size and modify time (the default \"metadata\" behavior) may return what appear to be \"false positives\", \
@@ -232,7 +394,70 @@ fn parse_args() -> ArgMatches {
                 or a user can simply update the modify time via 'touch'.  If only this flag is specified, the \"contents\" option compares the actual file contents of file versions, if their sizes match, \
                 and overrides the default \"metadata\" behavior.  The \"contents\" option can be expensive, as the file versions need to be read back and compared, and should probably only be used for smaller files.  \
                 Given how expensive this operation can be, for larger files or files with many versions, \"contents\" option is not shown in Interactive browse mode, \
-                but after a selection is made, can be utilized in Select or Restore modes.  The \"all\" or \"no-filter\" option dumps all snapshot versions, and no attempt is made to determine if the file versions are distinct.")
+                but after a selection is made, can be utilized in Select or Restore modes.  The \"all\" or \"no-filter\" option dumps all snapshot versions, and no attempt is made to determine if the file versions are distinct.  \
+                The \"snap-time\" option considers versions distinct by the time their snapshots were taken, instead of by their modify time, \
+                so each snapshot is displayed once, in the order taken, even where a snapshot is replicated to more than one dataset.")
+                .display_order(9)
+        )
+        .arg(
+            Arg::new("UNTIL")
+                .long("until")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("DATE")
+                .help("only display snapshot versions modified at or before DATE.  \
+                DATE is in local time, unless UTC is specified, like \"2023-01-31\", \"2023-01-31 13:30\", or \"2023-01-31T13:30:00\", \
+                or is an RFC 3339 date, like \"2023-01-31T13:30:00-05:00\".")
+                .conflicts_with("RECOVER_DELETED")
+                .display_order(9)
+        )
+        .arg(
+            Arg::new("MIN_SIZE")
+                .long("min-size")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("SIZE")
+                .help("only display snapshot versions at least SIZE in size.  SIZE is a number of bytes, or a number followed by a unit, \
+                like \"512K\", \"1.5M\", or \"2GiB\", in which units are powers of 1024.")
+                .display_order(9)
+        )
+        .arg(
+            Arg::new("MAX_SIZE")
+                .long("max-size")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("SIZE")
+                .help("only display snapshot versions at most SIZE in size.  SIZE is a number of bytes, or a number followed by a unit, \
+                like \"512K\", \"1.5M\", or \"2GiB\", in which units are powers of 1024.")
+                .display_order(9)
+        )
+        .arg(
+            Arg::new("SORT")
+                .long("sort")
+                .takes_value(true)
+                .require_equals(true)
+                .possible_values(["mtime", "size", "snapshot-date", "name"])
+                .help("order snapshot versions by their modify time (the default \"mtime\" behavior), by their size, \
+                by the creation date of the snapshot on which each resides, or by the name of that snapshot.")
+                .conflicts_with_all(&["OUNCE", "WATCH", "CHANGELOG", "EXPORT_GIT", "DIR_SUMMARY", "DIFF", "NUM_VERSIONS"])
+                .display_order(9)
+        )
+        .arg(
+            Arg::new("REVERSE")
+                .long("reverse")
+                .help("display snapshot versions in reverse order, like newest first.")
+                .conflicts_with_all(&["OUNCE", "WATCH", "CHANGELOG", "EXPORT_GIT", "DIR_SUMMARY", "DIFF", "NUM_VERSIONS"])
+                .display_order(9)
+        )
+        .arg(
+            Arg::new("LIMIT")
+                .long("limit")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("NUM")
+                .help("display no more than NUM snapshot versions of each file, after any sort, \
+                so, to display only the 3 newest versions: \"httm --reverse --limit=3 file\".")
+                .conflicts_with_all(&["OUNCE", "WATCH", "CHANGELOG", "EXPORT_GIT", "DIR_SUMMARY", "DIFF", "NUM_VERSIONS"])
                 .display_order(9)
         )
         .arg(
@@ -257,6 +482,178 @@ fn parse_args() -> ArgMatches {
                 .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
                 .display_order(11)
         )
+        .arg(
+            Arg::new("SNAP_TEMPLATE")
+                .long("snap-template")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("SNAPSHOT")
+                .help("in SNAPSHOT mode, name new snapshots according to a template, like: \"{dataset}@{prefix}-{date:%Y%m%d-%H%M}-{suffix}\".  \
+                Available placeholders are: {dataset}, {prefix} (which is always \"snap\"), {suffix} (the value given at SNAPSHOT), and {date}, \
+                which optionally takes a strftime-like format, like {date:%Y%m%d}.  A template without \"{dataset}@\" names only the snapshot.  \
+                The default template is \"{dataset}@{prefix}_{date}_{suffix}\".  Note: Only snapshots with default names are considered by PRUNE.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("SNAP_RECURSIVE")
+                .long("snap-recursive")
+                .requires("SNAPSHOT")
+                .help("in SNAPSHOT mode, also snapshot all descendent datasets of each dataset snapshot, as with \"zfs snapshot -r\".")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("SNAP_PROPERTY")
+                .short('o')
+                .long("snap-property")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .requires("SNAPSHOT")
+                .value_name("NAME=VALUE")
+                .help("in SNAPSHOT mode, set a property upon each new snapshot, usually a user property, like: \"-o com.example:reason=upgrade\".  \
+                This argument may be given more than once.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("OUNCE")
+                .long("ounce")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("ounceSnapFileMount")
+                .help("snapshot the datasets of files given as arguments to another program, but only when those files have changes \
+                not yet committed to a snapshot, and then execute that program, like: \"httm --ounce -- vim file1 file2\".  \
+                A file needs a snapshot when its last snapshot version differs from its live version (see \"--last-snap=no-ditto-exclusive\").  \
+                Snapshots are taken in a batch per pool.  This argument optionally takes a value for a snapshot suffix.  The default suffix is 'ounceSnapFileMount'.  \
+                Note: This is a ZFS only option.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "JSON", "FORMAT", "FORMAT_TEMPLATE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("BACKGROUND")
+                .long("background")
+                .requires("OUNCE")
+                .help("in OUNCE mode, check for and take any snapshots in the background, while the program executes, so the program starts immediately.  \
+                Safest for programs which do not modify files immediately, like an editor.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("WATCH")
+                .long("watch")
+                .help("watch the input paths for writes and, once changes have gone quiet, snapshot only the datasets which actually changed.  \
+                Directories are watched recursively.  httm continues to watch until interrupted.  \
+                See also SNAP_SUFFIX, DEBOUNCE, RATE_LIMIT, and MAX_SNAPS_PER_HOUR.  \
+                Note: This is a ZFS only option.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "JSON", "FORMAT", "FORMAT_TEMPLATE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("SNAP_SUFFIX")
+                .long("snap-suffix")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("WATCH")
+                .help("in WATCH mode, the suffix of the snapshots taken.  The default suffix is 'httmWatch'.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("DEBOUNCE")
+                .long("debounce")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("WATCH")
+                .help("in WATCH mode, how long a dataset must go without further changes before it is snapshot, like \"30s\", \"5m\", or \"1h\".  \
+                The default is \"30s\".")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("RATE_LIMIT")
+                .long("rate-limit")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("WATCH")
+                .help("in WATCH mode, the minimum time between snapshots of the same dataset, like \"30s\", \"5m\", or \"1h\".  \
+                Changes made in the meantime are included in the next snapshot.  The default is \"5m\".")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("MAX_SNAPS_PER_HOUR")
+                .long("max-snaps-per-hour")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("WATCH")
+                .help("in WATCH mode, the maximum number of snapshots, across all datasets, httm will take in any hour, \
+                so runaway churn can't create runaway snapshots.  The default is 12.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("PRUNE")
+                .long("prune")
+                .help("destroy snapshots httm itself created, for the datasets of the input paths, which fall outside a \
+                grandfather-father-son retention policy, like: \"httm --prune --keep-hourly=24 --keep-daily=14 --keep-weekly=8 /home\".  \
+                The creation time of each snapshot is read from its httm snapshot name, and snapshots httm did not create are never considered.  \
+                httm marks each snapshot it creates with the user property \"httm:created\", and, by default, only snapshots so marked are pruned.  \
+                When SUFFIX is given, httm falls back to matching the snapshot name alone, so snapshots taken before httm set this property may be pruned too.  \
+                The snapshots to be destroyed are shown, and destroyed only upon the user's consent.  \
+                See also KEEP_HOURLY, KEEP_DAILY, KEEP_WEEKLY, SUFFIX, and YES.  \
+                Note: This is a ZFS only option.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "JSON", "FORMAT", "FORMAT_TEMPLATE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("KEEP_HOURLY")
+                .long("keep-hourly")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("PRUNE")
+                .help("in PRUNE mode, keep the newest snapshot from each of this many of the most recent hours which have a snapshot.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("KEEP_DAILY")
+                .long("keep-daily")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("PRUNE")
+                .help("in PRUNE mode, keep the newest snapshot from each of this many of the most recent days which have a snapshot.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("KEEP_WEEKLY")
+                .long("keep-weekly")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("PRUNE")
+                .help("in PRUNE mode, keep the newest snapshot from each of this many of the most recent ISO weeks which have a snapshot.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("SUFFIX")
+                .long("suffix")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("PRUNE")
+                .help("in PRUNE mode, only consider httm snapshots with this suffix, like \"ounce\" or \"httmSnapFileMount\".  \
+                Retention is then computed among only those snapshots.  By default, all httm snapshots are considered.  \
+                Snapshots are matched by name alone, so those without the \"httm:created\" property are considered too.")
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("YES")
+                .long("yes")
+                .help("in PURGE or PRUNE mode, destroy snapshots without asking for the user's consent, so no terminal is required, \
+                as when run from cron.  Careless use may cause you to lose snapshot data you care about.")
+                .requires("DESTROY_MODE")
+                .conflicts_with_all(&["DRY_RUN"])
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("DRY_RUN")
+                .long("dry-run")
+                .help("in PURGE or PRUNE mode, only print the snapshots which would be destroyed, and the space destroying them would reclaim, \
+                and destroy nothing.  In PURGE mode, this output may also be printed as JSON.")
+                .requires("DESTROY_MODE")
+                .display_order(11)
+        )
         .arg(
             Arg::new("LIST_SNAPS")
                 .long("list-snaps")
@@ -274,6 +671,18 @@ fn parse_args() -> ArgMatches {
                 .conflicts_with_all(&["BROWSE", "RESTORE"])
                 .display_order(12)
         )
+        .arg(
+            Arg::new("WITH_SPACE")
+                .long("with-space")
+                .requires("LIST_SNAPS")
+                .help("in LIST_SNAPS mode, also display the space used, written, and referenced by each snapshot listed, \
+                and an estimate of the space destroying all the listed snapshots would reclaim.  \
+                Space destroying a set of snapshots would reclaim may be much larger than the sum of each snapshot's used space, \
+                as data shared only among those snapshots is reclaimed too.  \
+                Note: This is a ZFS only option.")
+                .conflicts_with_all(&["PURGE", "FORMAT"])
+                .display_order(12)
+        )
         .arg(
             Arg::new("ROLL_FORWARD")
                 .long("roll-forward")
@@ -288,6 +697,145 @@ fn parse_args() -> ArgMatches {
                 .conflicts_with_all(&["BROWSE", "RESTORE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
                 .display_order(13)
         )
+        .arg(
+            Arg::new("DIFF")
+                .long("diff")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("unified")
+                .possible_values(["unified", "side-by-side"])
+                .help("display the differences between two file versions, in the order given, without requiring any external diff program.  \
+                Either path may be a snapshot version or a live version.  \
+                If only a single snapshot version is given, httm compares it to its live version, and, if only a single live version is given, \
+                httm compares the most recent snapshot version to the live version.  \
+                The default \"unified\" format is like \"diff -u\", and \"side-by-side\" is like \"diff -y\".  \
+                Binary files are detected and only compared by contents, and a size summary is always printed.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "JSON", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("CHANGELOG")
+                .long("changelog")
+                .help("display how the input file/s changed over time.  For each unique version, httm prints the version's date, snapshot name, and size delta, \
+                and a unified diff against the previous unique version, ending with the live version.  \
+                Unless otherwise specified at UNIQUENESS, versions are compared by contents (\"--uniqueness=contents\").  \
+                When JSON is also specified, each diff is included as a patch string.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("DIR_SUMMARY")
+                .long("dir-summary")
+                .help("display how the input directory/s changed over time.  For each unique snapshot version of a directory, httm walks the directory's contents, \
+                and prints the snapshot's date and name, the number of files and their total size, \
+                and the number of entries added (+), removed (-), and changed (~) compared with the previous unique version, ending with the live version.  \
+                Snapshot versions whose contents are unchanged from the previous version are omitted.  \
+                Unless otherwise specified at UNIQUENESS, all snapshot versions are considered, as a directory's modify time may not change when its contents change.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("TREE_DIFF")
+                .long("tree-diff")
+                .help("display the differences between two versions of a directory, like: \"httm --tree-diff DIR SNAP_A [SNAP_B|live]\".  \
+                httm walks both versions of DIR, and displays each entry created (+), removed (-), modified (M), or renamed (R), with its size.  \
+                If SNAP_B is not specified, SNAP_A is compared with the live version of DIR.  \
+                A snapshot may be specified by its full name (as displayed by LIST_SNAPS), by the final component of its snapshot mount, or by its snapshot mount path.  \
+                Renames are detected where the filesystem preserves inode numbers in snapshots, like ZFS, and are not detected for alias mapped directories.  \
+                Unlike ROLL_FORWARD, TREE_DIFF requires neither the 'zfs diff' command nor root privileges.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("GREP")
+                .long("grep")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("PATTERN")
+                .help("search the contents of each unique version of the input files, or, for an input directory, of every file beneath it, \
+                for lines matching the regular expression PATTERN, like: \"httm --grep='quarterly (report|summary)' ~/Documents\".  \
+                Files deleted from an input directory, or from any directory beneath it, are searched too, by their snapshot versions, \
+                but the contents of a deleted directory are not.  \
+                httm displays each version which matches, with the line number and text of each matching line.  Binary files are skipped.  \
+                Unless otherwise specified at UNIQUENESS, versions are compared by contents (\"--uniqueness=contents\").  \
+                If no path is given, the working directory is searched.  Prefix PATTERN with \"(?i)\" for a case insensitive search.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("FIND")
+                .long("find")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("NAME-GLOB")
+                .help("search every snapshot of every dataset for entries whose names match the glob NAME-GLOB, like: \"httm --find='*.xlsx'\", \
+                even those whose parent directories no longer exist.  If NAME-GLOB contains a \"/\", it is matched against each entry's path relative to its dataset's mount, \
+                like: \"httm --find='**/taxes/*.pdf'\".  In NAME-GLOB, \"*\" and \"?\" never match a \"/\", and \"**\" matches any number of directories.  \
+                httm displays only the newest snapshot version of each entry, and whether the entry still exists live.  \
+                Snapshot directories whose contents are unchanged from a previously searched snapshot are not read again.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("DATASET")
+                .long("dataset")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("DATASET")
+                .help("limit a FIND search to the snapshots of a single dataset, specified by its mount, like \"/home\", or by its name, like \"rpool/home\".")
+                .requires("FIND")
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("EXPORT_GIT")
+                .long("export-git")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("DEST")
+                .help("export the snapshot history of a single file or directory, as a bare git repository, to a new directory DEST, \
+                or, if DEST ends with \".tar\", a tar archive of that bare repository.  httm writes one commit per distinct version, in time order, \
+                ending with the live version, with the author date set to the version's modify time, and the snapshot name in the commit message.  \
+                Unless otherwise specified at UNIQUENESS, all snapshot versions are considered, as a directory's modify time may not change when its contents change.  \
+                No git executable is required.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "JSON", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("RECOVER_DELETED")
+                .long("recover-deleted")
+                .help("restore every file and directory deleted from beneath the directory specified, like: \"httm --recover-deleted /home/user/project\".  \
+                Each is restored from its last in time snapshot version, the same version displayed in deleted modes, with its attributes preserved, \
+                and a deleted directory is restored with all of its contents.  By default, each is restored to its original location.  \
+                httm will never overwrite a live file, and prints a manifest of what was restored.  See also SINCE and TO.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "LAST_SNAP", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("SINCE")
+                .long("since")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("DATE")
+                .help("only display snapshot versions modified at or after DATE.  \
+                In RECOVER_DELETED mode, instead, only restore files and directories which may have been deleted at or after DATE, \
+                that is, those for which no snapshot taken before DATE shows them as already deleted.  \
+                DATE is in local time, unless UTC is specified, like \"2023-01-31\", \"2023-01-31 13:30\", or \"2023-01-31T13:30:00\", \
+                or is an RFC 3339 date, like \"2023-01-31T13:30:00-05:00\".")
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("TO")
+                .long("to")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("DEST")
+                .value_parser(clap::builder::ValueParser::os_string())
+                .requires("RECOVER_DELETED")
+                .help("in RECOVER_DELETED mode, restore to the directory DEST, recreating the layout of the directory specified beneath DEST, \
+                instead of restoring to each file's original location.")
+                .display_order(13)
+        )
         .arg(
             Arg::new("PURGE")
                 .long("purge")
@@ -301,6 +849,32 @@ fn parse_args() -> ArgMatches {
                 .requires("LIST_SNAPS")
                 .display_order(13)
         )
+        .arg(
+            Arg::new("HOLD")
+                .long("hold")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("TAG")
+                .help("place a hold, with the tag given, on all snapshot/s which contain the input file/s via \"zfs hold\", \
+                so those snapshots can't be destroyed, as by an automatic snapshot retention policy, until the hold is released.  \
+                The snapshots held may be filtered by UNIQUENESS, and, by appending a comma, by the same values LIST_SNAPS accepts.  \
+                A value of \"incident,5,prep_Apt\" would hold, with the tag \"incident\", only the last 5 (at most) snapshots which contain \"prep_Apt\".  \
+                Note: This is a ZFS only option.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR", "JSON", "FORMAT", "FORMAT_TEMPLATE", "WITH_SPACE"])
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("RELEASE")
+                .long("release")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("TAG")
+                .help("release a hold, with the tag given, from all snapshot/s which contain the input file/s via \"zfs release\".  \
+                The snapshots released may be filtered by UNIQUENESS, and, by appending a comma, by the same values LIST_SNAPS accepts, as with HOLD.  \
+                Note: This is a ZFS only option.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "PURGE", "ROLL_FORWARD", "FILE_MOUNT", "NUM_VERSIONS", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR", "JSON", "FORMAT", "FORMAT_TEMPLATE", "WITH_SPACE"])
+                .display_order(13)
+        )
         .arg(
             Arg::new("FILE_MOUNT")
                 .short('m')
@@ -371,6 +945,46 @@ fn parse_args() -> ArgMatches {
                 .conflicts_with_all(&["SELECT", "RESTORE"])
                 .display_order(19)
         )
+        .arg(
+            Arg::new("FORMAT")
+                .long("format")
+                .takes_value(true)
+                .possible_values(["csv", "tsv"])
+                .require_equals(true)
+                .help("display the ordinary output, but as comma or tab separated values, with a header row, for import into spreadsheets and databases.  \
+                Each row describes a single version of an input file with the following columns: \
+                path, version_path, snapshot_name, dataset, size_bytes, mtime_epoch, mtime_formatted and is_live.  \
+                Fields which contain a delimiter, a quotation mark or a newline are quoted according to RFC 4180.  \
+                Available in the display, NUM_VERSIONS, LIST_SNAPS and FILE_MOUNT modes.  \
+                In FILE_MOUNT mode, the version_path is the mount point of the dataset which contains the input file.")
+                .conflicts_with_all(&["JSON", "RAW", "ZEROS", "NOT_SO_PRETTY", "SELECT", "RESTORE"])
+                .display_order(19)
+        )
+        .arg(
+            Arg::new("FORMAT_TEMPLATE")
+                .long("format-template")
+                .alias("template")
+                .takes_value(true)
+                .require_equals(true)
+                .help("display each version line according to a user defined template, instead of the ordinary date, size and path layout.  \
+                Placeholders are enclosed in braces: \"{path}\", \"{relative_path}\", \"{size}\", \"{mtime}\", \"{snap_name}\", \"{snap_time}\", \"{dataset}\" and \"{fs_type}\".  \
+                \"{size:h}\" displays a human readable size (the default), and \"{size:b}\" displays the size in bytes.  \
+                \"{mtime}\" and \"{snap_time}\" optionally take a strftime-like date format, like \"{mtime:%Y-%m-%d %H:%M}\".  \
+                Use \"{{\" and \"}}\" for literal braces.  Each placeholder is padded so that columns still align, and live paths are still colored.  \
+                For example: --format-template='{mtime:%Y-%m-%d} {size:h} {snap_name} {path}'")
+                .conflicts_with_all(&["JSON", "FORMAT", "RAW", "ZEROS", "NUM_VERSIONS", "LAST_SNAP"])
+                .display_order(19)
+        )
+        .arg(
+            Arg::new("SNAP_INFO")
+                .long("snap-info")
+                .help("display, beside each snapshot version, when its snapshot was taken, the snapshot's name, and its dataset, \
+                as a version's modify time is when the file was last modified, not when the snapshot was taken.  \
+                When JSON is also specified, each version includes the fields \"snapshot_name\", \"snapshot_time\" and \"dataset\".  \
+                For ZFS, a snapshot's time is its creation time, for other filesystems, the modify time of its snapshot mount.")
+                .conflicts_with_all(&["FORMAT", "FORMAT_TEMPLATE", "NUM_VERSIONS"])
+                .display_order(19)
+        )
         .arg(
             Arg::new("OMIT_DITTO")
                 .long("omit-ditto")
@@ -416,6 +1030,28 @@ fn parse_args() -> ArgMatches {
                 .requires("DELETED")
                 .display_order(25)
         )
+        .arg(
+            Arg::new("DETECT_RENAMES")
+                .long("detect-renames")
+                .help("in deleted file modes, and in RECOVER_DELETED mode, don't treat a file or directory which was renamed as deleted.  \
+                An entry which appears deleted is matched to a live entry in the same directory, whose name the snapshot doesn't contain, \
+                which has the same inode number (where the filesystem preserves inode numbers in snapshots, like ZFS), or, for regular files, the same contents.  \
+                Only the same directory is searched, so an entry moved to another directory will still appear deleted.  \
+                Such entries are labeled as renamed, with their new name, instead of displayed as deleted, and are never recovered.")
+                .conflicts_with_all(&["SNAPSHOT", "LAST_SNAP", "NO_SNAP", "FORMAT", "FORMAT_TEMPLATE"])
+                .display_order(25)
+        )
+        .arg(
+            Arg::new("WHEN")
+                .long("when")
+                .help("in deleted file modes, estimate when each deleted file was deleted.  \
+                httm finds the last snapshot which contains the file and the first snapshot taken thereafter which does not, \
+                and displays the window between the two.  In interactive modes, the window is displayed beside each deleted file.  \
+                For ZFS, a snapshot's time is its creation time, for other filesystems, the modify time of its snapshot mount.")
+                .conflicts_with_all(&["SNAPSHOT", "LAST_SNAP", "NO_SNAP", "FORMAT", "FORMAT_TEMPLATE"])
+                .requires("DELETED")
+                .display_order(25)
+        )
         .arg(
             Arg::new("MAP_ALIASES")
                 .long("map-aliases")
@@ -441,7 +1077,7 @@ fn parse_args() -> ArgMatches {
                 .help("detect and display the number of unique versions available (e.g. one, \"1\", \
                 version is available if either a snapshot version exists, and is identical to live version, or only a live version exists).  \
                 This argument optionally takes a value.  The default value, \"all\", will print the filename and number of versions, \
-                \"graph\" will print the filename and a line of characters representing the number of versions, \
+                \"graph\" will print the filename and a timeline of its versions, in which each version is marked by its modify time, counted by day, week, or month, \
                 \"single\" will print only filenames which only have one version, \
                 (and \"single-no-snap\" will print those without a snap taken, and \"single-with-snap\" will print those with a snap taken), \
                 and \"multiple\" will print only filenames which only have multiple versions.")
@@ -472,12 +1108,87 @@ fn parse_args() -> ArgMatches {
                 .value_parser(clap::builder::ValueParser::os_string())
                 .display_order(29)
         )
+        .arg(
+            Arg::new("PRE_HOOK")
+                .long("pre-hook")
+                .takes_value(true)
+                .require_equals(true)
+                .multiple_occurrences(true)
+                .value_name("COMMAND")
+                .help("execute a shell command before httm takes a snapshot, restores a file, purges snapshots, or rolls forward.  \
+                The command receives a JSON description of the operation (the hook type, operation, paths, snapshot names, user, and uid) on its stdin.  \
+                If the command fails, httm aborts the operation.  This argument may be given more than once.  \
+                Hooks may also be configured for specific operations in a hooks file, see HOOKS_FILE.")
+                .display_order(29)
+        )
+        .arg(
+            Arg::new("POST_HOOK")
+                .long("post-hook")
+                .takes_value(true)
+                .require_equals(true)
+                .multiple_occurrences(true)
+                .value_name("COMMAND")
+                .help("execute a shell command after httm successfully takes a snapshot, restores a file, purges snapshots, or rolls forward.  \
+                The command receives the same JSON description as PRE_HOOK on its stdin.  A failing post hook only produces a warning.  \
+                This argument may be given more than once.")
+                .display_order(29)
+        )
+        .arg(
+            Arg::new("HOOKS_FILE")
+                .long("hooks-file")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("PATH")
+                .help("read hooks from a file, where each line is like \"pre.snapshot = /usr/local/bin/quiesce-db\".  \
+                A key begins with \"pre\" or \"post\", and ends with an operation: \"snapshot\", \"restore\", \"purge\", \"roll-forward\", or \"*\" for any operation.  \
+                Lines beginning with '#' are ignored.  By default, httm reads hooks from \"$XDG_CONFIG_HOME/httm/hooks.conf\" or \"$HOME/.config/httm/hooks.conf\", if either exists.")
+                .display_order(29)
+        )
+        .arg(
+            Arg::new("AUDIT_LOG")
+                .long("audit-log")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("PATH")
+                .help("append a JSON record of each operation which changes live data or destroys snapshots, that is, each restore, purge, and roll forward, \
+                to the file given, or, given the value \"syslog\", send each record to syslog (and journald).  \
+                Each record includes a timestamp, the uid and user, the hostname, the command line, the paths affected, the snapshot names, the bytes written, and the outcome.  \
+                When httm is executed as root, the audit log is enabled by default, at \"/var/log/httm/audit.jsonl\".")
+                .conflicts_with("NO_AUDIT_LOG")
+                .display_order(29)
+        )
+        .arg(
+            Arg::new("NO_AUDIT_LOG")
+                .long("no-audit-log")
+                .help("disable the audit log, even when httm is executed as root.")
+                .display_order(29)
+        )
         .arg(
             Arg::new("UTC")
                 .long("utc")
                 .help("use UTC for date display and timestamps")
                 .display_order(30)
         )
+        .arg(
+            Arg::new("DATE_FORMAT")
+                .long("date-format")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("FORMAT")
+                .help("display dates in FORMAT, which is either a strftime-like pattern, like \"%Y-%m-%d %H:%M\", \
+                or one of \"default\", \"timestamp\", or \"rfc3339\" (or \"iso8601\"), like \"2023-01-31T13:30:00-05:00\", for machine output.  \
+                Dates are displayed in the local time zone, unless UTC is specified.  \
+                With JSON, dates are also in FORMAT, and, with RAW or ZEROS, in FORMAT instead of in seconds since the epoch.")
+                .display_order(30)
+        )
+        .arg(
+            Arg::new("RELATIVE_DATES")
+                .long("relative-dates")
+                .help("display dates relative to now, like \"3 hours ago\" or \"2 days ago\", in the display and interactive modes.  \
+                JSON dates are not relative.")
+                .conflicts_with("DATE_FORMAT")
+                .display_order(30)
+        )
         .arg(
             Arg::new("DEBUG")
                 .long("debug")
@@ -491,6 +1202,17 @@ fn parse_args() -> ArgMatches {
                 .exclusive(true)
                 .display_order(32)
         )
+        .group(
+            // each of these modes is its own ExecMode, and only the first, in the order
+            // Config::from_matches checks for them, would run, so only one may be requested
+            ArgGroup::new("EXCLUSIVE_MODE")
+                .args(&["OUNCE", "WATCH", "PRUNE", "RECOVER_DELETED", "EXPORT_GIT", "CHANGELOG", "DIFF", "DIR_SUMMARY", "TREE_DIFF", "GREP", "FIND", "HOLD", "RELEASE"])
+        )
+        .group(
+            // YES and DRY_RUN only mean something to the modes which destroy snapshots
+            ArgGroup::new("DESTROY_MODE")
+                .args(&["PURGE", "PRUNE"])
+        )
         .get_matches()
 }
 
@@ -505,17 +1227,26 @@ pub struct Config {
     pub opt_omit_ditto: bool,
     pub opt_no_hidden: bool,
     pub opt_json: bool,
+    pub opt_delimited_format: Option<DelimitedFormat>,
+    pub opt_format_template: Option<FormatTemplate>,
     pub uniqueness: ListSnapsOfType,
     pub opt_bulk_exclusion: Option<BulkExclusion>,
     pub opt_last_snap: Option<LastSnapMode>,
     pub opt_preview: Option<String>,
     pub opt_deleted_mode: Option<DeletedMode>,
+    pub opt_deleted_when: bool,
+    pub opt_detect_renames: bool,
+    pub opt_snap_info: bool,
+    pub opt_version_filters: Option<VersionFilters>,
     pub opt_requested_dir: Option<PathData>,
     pub requested_utc_offset: UtcOffset,
+    pub requested_date_format: DateFormat,
     pub exec_mode: ExecMode,
     pub print_mode: PrintMode,
     pub dataset_collection: FilesystemInfo,
     pub pwd: PathData,
+    pub hooks: Hooks,
+    pub opt_audit_log: Option<AuditLog>,
 }
 
 impl Config {
@@ -542,8 +1273,44 @@ impl Config {
             UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC)
         };
 
+        let requested_date_format = if matches.is_present("RELATIVE_DATES") {
+            DateFormat::Relative
+        } else {
+            match matches.value_of("DATE_FORMAT") {
+                None | Some("default") => DateFormat::Display,
+                Some("timestamp") => DateFormat::Timestamp,
+                Some("rfc3339" | "iso8601" | "iso") => DateFormat::Rfc3339,
+                Some(strftime) => DateFormat::Custom(strftime_to_format_description(strftime)?),
+            }
+        };
+
         let opt_json = matches.is_present("JSON");
 
+        let opt_delimited_format = match matches.value_of("FORMAT") {
+            Some("csv") => Some(DelimitedFormat::Csv),
+            Some("tsv") => Some(DelimitedFormat::Tsv),
+            _ => None,
+        };
+
+        // the audit log is on by default for root, who can most easily do the most damage
+        let opt_audit_log = if matches.is_present("NO_AUDIT_LOG") {
+            None
+        } else {
+            match matches.value_of_os("AUDIT_LOG") {
+                Some(value) if value == "syslog" => Some(AuditLog::Syslog),
+                Some(value) => Some(AuditLog::JsonLines(PathBuf::from(value))),
+                None if nix::unistd::geteuid().is_root() => {
+                    Some(AuditLog::JsonLines(PathBuf::from(DEFAULT_AUDIT_LOG)))
+                }
+                None => None,
+            }
+        };
+
+        let opt_format_template = match matches.value_of("FORMAT_TEMPLATE") {
+            Some(raw_template) => Some(FormatTemplate::new(raw_template)?),
+            None => None,
+        };
+
         let mut print_mode = if matches.is_present("ZEROS") {
             PrintMode::RawZero
         } else if matches.is_present("RAW") {
@@ -577,6 +1344,10 @@ impl Config {
         let opt_no_filter = matches.is_present("NO_FILTER");
         let opt_debug = matches.is_present("DEBUG");
         let opt_no_hidden = matches.is_present("FILTER_HIDDEN");
+        let opt_deleted_when = matches.is_present("WHEN");
+        let opt_detect_renames = matches.is_present("DETECT_RENAMES");
+        let opt_snap_info = matches.is_present("SNAP_INFO");
+        let opt_version_filters = Self::get_version_filters(matches, requested_utc_offset)?;
 
         let opt_last_snap = match matches.value_of("LAST_SNAP") {
             Some("" | "any") => Some(LastSnapMode::Any),
@@ -638,6 +1409,15 @@ impl Config {
         let mut uniqueness = match matches.value_of("UNIQUENESS") {
             Some("all" | "no-filter") => ListSnapsOfType::All,
             Some("contents") => ListSnapsOfType::UniqueContents,
+            Some("snap-time") => ListSnapsOfType::UniqueSnapTime,
+            // a changelog of versions which differ only by metadata would be mostly empty diffs
+            None if matches.is_present("CHANGELOG") => ListSnapsOfType::UniqueContents,
+            // versions which differ only by metadata would only repeat the same matches
+            None if matches.is_present("GREP") => ListSnapsOfType::UniqueContents,
+            // duplicate versions are dropped when their git trees are identical
+            None if matches.is_present("EXPORT_GIT") => ListSnapsOfType::All,
+            // duplicate versions are dropped when their contents are unchanged
+            None if matches.is_present("DIR_SUMMARY") => ListSnapsOfType::All,
             Some("metadata" | _) | None => ListSnapsOfType::UniqueMetadata,
         };
 
@@ -665,20 +1445,28 @@ impl Config {
 
         let opt_snap_file_mount =
             if let Some(requested_snapshot_suffix) = matches.value_of("SNAPSHOT") {
-                if requested_snapshot_suffix == "httmSnapFileMount" {
-                    Some(requested_snapshot_suffix.to_owned())
-                } else if requested_snapshot_suffix.contains(char::is_whitespace) {
+                if requested_snapshot_suffix.contains(char::is_whitespace) {
                     return Err(HttmError::new(
                         "httm will only accept snapshot suffixes which don't contain whitespace",
                     )
                     .into());
-                } else {
-                    Some(requested_snapshot_suffix.to_owned())
                 }
+
+                Some(Self::get_snapshot_options(
+                    matches,
+                    requested_snapshot_suffix,
+                )?)
             } else {
                 None
             };
 
+        // holds are placed on all snapshots which contain a file, like LIST_SNAPS, unless otherwise specified
+        if (matches.is_present("HOLD") || matches.is_present("RELEASE"))
+            && !matches.is_present("UNIQUENESS")
+        {
+            uniqueness = ListSnapsOfType::All;
+        }
+
         let opt_snap_mode_filters = if matches.is_present("LIST_SNAPS") {
             // allow selection of snaps to purge in purge mode
             let select_mode = matches!(opt_interactive_mode, Some(InteractiveMode::Select));
@@ -692,13 +1480,16 @@ impl Config {
                 uniqueness = ListSnapsOfType::All;
             }
 
+            let with_space = matches.is_present("WITH_SPACE");
+
             if let Some(values) = matches.value_of("LIST_SNAPS") {
-                Some(Self::get_snap_filters(values, select_mode)?)
+                Some(Self::get_snap_filters(values, select_mode, with_space)?)
             } else {
                 Some(ListSnapsFilters {
                     select_mode,
                     omit_num_snaps: 0usize,
                     name_filters: None,
+                    with_space,
                 })
             }
         } else {
@@ -707,16 +1498,80 @@ impl Config {
 
         let mut exec_mode = if let Some(snap_name) = matches.value_of("ROLL_FORWARD") {
             ExecMode::RollForward(snap_name.to_string())
+        } else if matches.is_present("WATCH") {
+            ExecMode::Watch(Self::get_watch_options(matches)?)
+        } else if matches.is_present("PRUNE") {
+            ExecMode::Prune(Self::get_prune_options(matches)?)
+        } else if let Some(suffix) = matches.value_of("OUNCE") {
+            if suffix.contains(char::is_whitespace) {
+                return Err(HttmError::new(
+                    "httm will only accept snapshot suffixes which don't contain whitespace",
+                )
+                .into());
+            }
+
+            let command: Vec<OsString> = matches
+                .values_of_os("INPUT_FILES")
+                .map(|values| values.map(OsStr::to_os_string).collect())
+                .unwrap_or_default();
+
+            if command.is_empty() {
+                return Err(HttmError::new(
+                    "OUNCE requires a program to execute, like: \"httm --ounce -- vim file1 file2\"",
+                )
+                .into());
+            }
+
+            ExecMode::Ounce(OunceOptions {
+                command,
+                suffix: suffix.to_owned(),
+                background: matches.is_present("BACKGROUND"),
+            })
+        } else if matches.is_present("RECOVER_DELETED") {
+            ExecMode::RecoverDeleted(Self::get_recover_options(matches, requested_utc_offset)?)
+        } else if let Some(dest) = matches.value_of_os("EXPORT_GIT") {
+            ExecMode::ExportGit(PathBuf::from(dest))
+        } else if matches.is_present("CHANGELOG") {
+            ExecMode::Changelog
+        } else if matches.is_present("DIR_SUMMARY") {
+            ExecMode::DirSummary
+        } else if let Some(pattern) = matches.value_of("GREP") {
+            let regex = regex::Regex::new(pattern).map_err(|err| {
+                HttmError::with_context("httm could not parse the GREP pattern given", &err)
+            })?;
+
+            ExecMode::Grep(regex)
+        } else if let Some(glob) = matches.value_of("FIND") {
+            ExecMode::Find(FindOptions {
+                regex: glob_to_regex(glob)?,
+                match_relative_path: glob.contains('/'),
+                opt_dataset: matches
+                    .value_of("DATASET")
+                    .map(|dataset| dataset.to_owned()),
+            })
+        } else if matches.is_present("TREE_DIFF") {
+            ExecMode::TreeDiff(Self::get_tree_diff_options(matches)?)
+        } else if let Some(diff_mode) = matches.value_of("DIFF") {
+            match diff_mode {
+                "side-by-side" => ExecMode::Diff(DiffMode::SideBySide),
+                _ => ExecMode::Diff(DiffMode::Unified),
+            }
         } else if let Some(num_versions_mode) = opt_num_versions {
             ExecMode::NumVersions(num_versions_mode)
         } else if let Some(mount_display) = opt_mount_display {
             ExecMode::MountsForFiles(mount_display)
+        } else if let Some(value) = matches.value_of("HOLD") {
+            let (tag, opt_filters) = Self::get_hold_tag_and_filters(value)?;
+            ExecMode::Hold(opt_filters, HoldAction::Hold(tag))
+        } else if let Some(value) = matches.value_of("RELEASE") {
+            let (tag, opt_filters) = Self::get_hold_tag_and_filters(value)?;
+            ExecMode::Hold(opt_filters, HoldAction::Release(tag))
         } else if matches.is_present("PURGE") {
-            ExecMode::Purge(opt_snap_mode_filters)
+            ExecMode::Purge(opt_snap_mode_filters, Self::get_purge_consent(matches))
         } else if opt_snap_mode_filters.is_some() {
             ExecMode::SnapsForFiles(opt_snap_mode_filters)
-        } else if let Some(requested_snapshot_suffix) = opt_snap_file_mount {
-            ExecMode::SnapFileMount(requested_snapshot_suffix)
+        } else if let Some(snapshot_options) = opt_snap_file_mount {
+            ExecMode::SnapFileMount(snapshot_options)
         } else if let Some(interactive_mode) = opt_interactive_mode {
             ExecMode::Interactive(interactive_mode)
         } else if opt_deleted_mode.is_some() {
@@ -726,6 +1581,16 @@ impl Config {
             ExecMode::Display
         };
 
+        if opt_detect_renames
+            && opt_deleted_mode.is_none()
+            && !matches!(exec_mode, ExecMode::RecoverDeleted(_))
+        {
+            return Err(HttmError::new(
+                "DETECT_RENAMES is only available in deleted file modes, or in RECOVER_DELETED mode.",
+            )
+            .into());
+        }
+
         if opt_recursive {
             if matches!(exec_mode, ExecMode::Display) {
                 return Err(HttmError::new("RECURSIVE not available in Display Mode.").into());
@@ -765,6 +1630,22 @@ impl Config {
             .into());
         }
 
+        if opt_delimited_format.is_some()
+            && !matches!(
+                exec_mode,
+                ExecMode::Display
+                    | ExecMode::Interactive(InteractiveMode::Browse)
+                    | ExecMode::NumVersions(_)
+                    | ExecMode::SnapsForFiles(_)
+                    | ExecMode::MountsForFiles(_)
+            )
+        {
+            return Err(HttmError::new(
+                "FORMAT is only available in Display, Browse, NUM_VERSIONS, LIST_SNAPS and FILE_MOUNT modes.",
+            )
+            .into());
+        }
+
         if opt_last_snap.is_some() && matches!(exec_mode, ExecMode::NonInteractiveRecursive(_)) {
             return Err(
                 HttmError::new("LAST_SNAP is not available in Display Recursive Mode.").into(),
@@ -792,6 +1673,25 @@ impl Config {
             &exec_mode,
         )?;
 
+        // only modes which modify files or snapshots run hooks, and a hooks file which fails
+        // to parse should not also break every mode which only reads
+        let hooks = if exec_mode.runs_hooks() {
+            let get_commands = |arg_name: &str| -> Vec<String> {
+                matches
+                    .values_of(arg_name)
+                    .map(|values| values.map(|value| value.to_owned()).collect())
+                    .unwrap_or_default()
+            };
+
+            Hooks::new(
+                &get_commands("PRE_HOOK"),
+                &get_commands("POST_HOOK"),
+                matches.value_of_os("HOOKS_FILE").map(Path::new),
+            )?
+        } else {
+            Hooks::default()
+        };
+
         let config = Config {
             paths,
             opt_bulk_exclusion,
@@ -805,14 +1705,23 @@ impl Config {
             opt_last_snap,
             opt_preview,
             opt_json,
+            opt_delimited_format,
+            opt_format_template,
             uniqueness,
             requested_utc_offset,
+            requested_date_format,
             exec_mode,
             print_mode,
             opt_deleted_mode,
+            opt_deleted_when,
+            opt_detect_renames,
+            opt_snap_info,
+            opt_version_filters,
             dataset_collection,
             pwd,
             opt_requested_dir,
+            hooks,
+            opt_audit_log,
         };
 
         Ok(config)
@@ -841,9 +1750,21 @@ impl Config {
         exec_mode: &ExecMode,
         pwd: &PathData,
     ) -> HttmResult<Vec<PathData>> {
+        // in ounce mode, the input files are a program and its arguments
+        if let ExecMode::Ounce(ounce_options) = exec_mode {
+            return Ok(Ounce::get_candidate_paths(&ounce_options.command));
+        }
+
+        // in tree diff mode, only the first input file is a path, the rest are snapshot names
+        if let ExecMode::TreeDiff(tree_diff_options) = exec_mode {
+            return Ok(vec![PathData::from(tree_diff_options.dir.as_path())]);
+        }
+
         let mut paths = if let Some(input_files) = opt_os_values {
+            // collect first, as, unlike par_bridge(), a par_iter() over a Vec preserves input order
             input_files
-                .par_bridge()
+                .collect::<Vec<_>>()
+                .into_par_iter()
                 .map(Path::new)
                 // canonicalize() on a deleted relative path will not exist,
                 // so we have to join with the pwd to make a path that
@@ -858,15 +1779,27 @@ impl Config {
                 // input, and waiting on one input from stdin is pretty silly
                 ExecMode::Interactive(_)
                 | ExecMode::NonInteractiveRecursive(_)
-                | ExecMode::RollForward(_) => {
+                | ExecMode::RollForward(_)
+                | ExecMode::Prune(_)
+                | ExecMode::RecoverDeleted(_)
+                | ExecMode::Grep(_)
+                | ExecMode::Find(_) => {
                     vec![pwd.clone()]
                 }
+                ExecMode::Ounce(_) => unreachable!("ounce mode paths are handled above"),
+                ExecMode::TreeDiff(_) => unreachable!("tree diff mode paths are handled above"),
                 ExecMode::Display
                 | ExecMode::SnapFileMount(_)
-                | ExecMode::Purge(_)
+                | ExecMode::Purge(..)
+                | ExecMode::Hold(..)
                 | ExecMode::MountsForFiles(_)
                 | ExecMode::SnapsForFiles(_)
-                | ExecMode::NumVersions(_) => read_stdin()?
+                | ExecMode::NumVersions(_)
+                | ExecMode::Diff(_)
+                | ExecMode::Changelog
+                | ExecMode::DirSummary
+                | ExecMode::ExportGit(_)
+                | ExecMode::Watch(_) => read_stdin()?
                     .par_iter()
                     .map(|string| PathData::from(Path::new(&string)))
                     .collect(),
@@ -875,7 +1808,9 @@ impl Config {
 
         // deduplicate pathdata and sort if in display mode --
         // so input of ./.z* and ./.zshrc will only print ./.zshrc once
-        paths = if paths.len() > 1 {
+        //
+        // except in diff mode, where the order given is the order compared
+        paths = if paths.len() > 1 && !matches!(exec_mode, ExecMode::Diff(_)) {
             paths.sort_unstable();
             // dedup needs to be sorted/ordered first to work (not like a BTreeMap)
             paths.dedup();
@@ -947,10 +1882,22 @@ impl Config {
             ExecMode::Display
             | ExecMode::RollForward(_)
             | ExecMode::SnapFileMount(_)
-            | ExecMode::Purge(_)
+            | ExecMode::Purge(..)
+            | ExecMode::Hold(..)
             | ExecMode::MountsForFiles(_)
             | ExecMode::SnapsForFiles(_)
-            | ExecMode::NumVersions(_) => {
+            | ExecMode::NumVersions(_)
+            | ExecMode::Diff(_)
+            | ExecMode::Changelog
+            | ExecMode::DirSummary
+            | ExecMode::TreeDiff(_)
+            | ExecMode::Grep(_)
+            | ExecMode::Find(_)
+            | ExecMode::ExportGit(_)
+            | ExecMode::Ounce(_)
+            | ExecMode::Watch(_)
+            | ExecMode::Prune(_)
+            | ExecMode::RecoverDeleted(_) => {
                 // in non-interactive mode / display mode, requested dir is just a file
                 // like every other file and pwd must be the requested working dir.
                 None
@@ -959,7 +1906,237 @@ impl Config {
         Ok(res)
     }
 
-    pub fn get_snap_filters(values: &str, select_mode: bool) -> HttmResult<ListSnapsFilters> {
+    pub fn get_snapshot_options(matches: &ArgMatches, suffix: &str) -> HttmResult<SnapshotOptions> {
+        let template = match matches.value_of("SNAP_TEMPLATE") {
+            Some(raw_template) => SnapNameTemplate::new(raw_template)?,
+            None => SnapNameTemplate::default(),
+        };
+
+        let properties: Vec<String> = match matches.values_of("SNAP_PROPERTY") {
+            Some(values) => values
+                .map(|property| match property.split_once('=') {
+                    Some((name, _value)) if !name.is_empty() => Ok(property.to_owned()),
+                    _ => {
+                        let msg = format!("SNAP_PROPERTY must be of the form \"name=value\", like \"com.example:reason=upgrade\", but was given: \"{property}\"");
+                        Err(HttmError::new(&msg))
+                    }
+                })
+                .collect::<Result<Vec<String>, HttmError>>()?,
+            None => Vec::new(),
+        };
+
+        Ok(SnapshotOptions {
+            suffix: suffix.to_owned(),
+            template,
+            recursive: matches.is_present("SNAP_RECURSIVE"),
+            properties,
+        })
+    }
+
+    pub fn get_watch_options(matches: &ArgMatches) -> HttmResult<WatchOptions> {
+        let suffix = matches.value_of("SNAP_SUFFIX").unwrap_or("httmWatch");
+
+        if suffix.contains(char::is_whitespace) {
+            return Err(HttmError::new(
+                "httm will only accept snapshot suffixes which don't contain whitespace",
+            )
+            .into());
+        }
+
+        let debounce = parse_duration(matches.value_of("DEBOUNCE").unwrap_or("30s"))?;
+        let rate_limit = parse_duration(matches.value_of("RATE_LIMIT").unwrap_or("5m"))?;
+
+        let max_per_hour = match matches.value_of("MAX_SNAPS_PER_HOUR") {
+            Some(value) => match value.parse::<usize>() {
+                Ok(number) if number > 0 => number,
+                _ => {
+                    return Err(HttmError::new(
+                        "MAX_SNAPS_PER_HOUR must be a number greater than 0.",
+                    )
+                    .into())
+                }
+            },
+            None => 12usize,
+        };
+
+        Ok(WatchOptions {
+            suffix: suffix.to_owned(),
+            debounce,
+            rate_limit,
+            max_per_hour,
+        })
+    }
+
+    pub fn get_purge_consent(matches: &ArgMatches) -> PurgeConsent {
+        if matches.is_present("DRY_RUN") {
+            PurgeConsent::DryRun
+        } else if matches.is_present("YES") {
+            PurgeConsent::AssumeYes
+        } else {
+            PurgeConsent::Interactive
+        }
+    }
+
+    pub fn get_prune_options(matches: &ArgMatches) -> HttmResult<PruneOptions> {
+        let parse_keep = |arg_name: &str| -> HttmResult<usize> {
+            match matches.value_of(arg_name) {
+                Some(value) => value.parse::<usize>().map_err(|_| {
+                    let msg = format!("{arg_name} must be a number greater than or equal to 0.");
+                    HttmError::new(&msg).into()
+                }),
+                None => Ok(0usize),
+            }
+        };
+
+        let keep_hourly = parse_keep("KEEP_HOURLY")?;
+        let keep_daily = parse_keep("KEEP_DAILY")?;
+        let keep_weekly = parse_keep("KEEP_WEEKLY")?;
+
+        // without a policy, every httm snapshot would be destroyed, which is more likely a mistake
+        if keep_hourly == 0 && keep_daily == 0 && keep_weekly == 0 {
+            return Err(HttmError::new(
+                "PRUNE requires a retention policy of at least one of KEEP_HOURLY, KEEP_DAILY, or KEEP_WEEKLY.",
+            )
+            .into());
+        }
+
+        Ok(PruneOptions {
+            keep_hourly,
+            keep_daily,
+            keep_weekly,
+            opt_suffix: matches.value_of("SUFFIX").map(|suffix| suffix.to_owned()),
+            consent: Self::get_purge_consent(matches),
+        })
+    }
+
+    pub fn get_recover_options(
+        matches: &ArgMatches,
+        requested_utc_offset: UtcOffset,
+    ) -> HttmResult<RecoverOptions> {
+        let opt_since = match matches.value_of("SINCE") {
+            Some(raw) => Some(parse_date(raw, requested_utc_offset)?),
+            None => None,
+        };
+
+        let opt_dest = match matches.value_of_os("TO") {
+            Some(dest) if !Path::new(dest).is_absolute() => {
+                let pwd = Self::get_pwd()?;
+                Some(pwd.path_buf.join(dest))
+            }
+            Some(dest) => Some(PathBuf::from(dest)),
+            None => None,
+        };
+
+        Ok(RecoverOptions {
+            opt_since,
+            opt_dest,
+        })
+    }
+
+    pub fn get_version_filters(
+        matches: &ArgMatches,
+        requested_utc_offset: UtcOffset,
+    ) -> HttmResult<Option<VersionFilters>> {
+        let parse_opt_date = |arg: &str| match matches.value_of(arg) {
+            Some(raw) => parse_date(raw, requested_utc_offset).map(Some),
+            None => Ok(None),
+        };
+
+        let parse_opt_size = |arg: &str| match matches.value_of(arg) {
+            Some(raw) => parse_size(raw).map(Some),
+            None => Ok(None),
+        };
+
+        // in RECOVER_DELETED mode, SINCE is a deletion time, not a modify time
+        let opt_since = if matches.is_present("RECOVER_DELETED") {
+            None
+        } else {
+            parse_opt_date("SINCE")?
+        };
+
+        let opt_sort = match matches.value_of("SORT") {
+            Some("size") => Some(VersionSort::Size),
+            Some("snapshot-date") => Some(VersionSort::SnapshotDate),
+            Some("name") => Some(VersionSort::SnapshotName),
+            Some(_) => Some(VersionSort::ModifyTime),
+            None => None,
+        };
+
+        let opt_limit = match matches.value_of("LIMIT") {
+            Some(raw) => match raw.parse::<usize>() {
+                Ok(limit) => Some(limit),
+                Err(err) => {
+                    return Err(HttmError::with_context(
+                        "httm could not parse the LIMIT given as a number",
+                        &err,
+                    )
+                    .into())
+                }
+            },
+            None => None,
+        };
+
+        let version_filters = VersionFilters {
+            opt_since,
+            opt_until: parse_opt_date("UNTIL")?,
+            opt_min_size: parse_opt_size("MIN_SIZE")?,
+            opt_max_size: parse_opt_size("MAX_SIZE")?,
+            opt_sort,
+            reverse: matches.is_present("REVERSE"),
+            opt_limit,
+        };
+
+        let is_empty = version_filters.opt_since.is_none()
+            && version_filters.opt_until.is_none()
+            && version_filters.opt_min_size.is_none()
+            && version_filters.opt_max_size.is_none()
+            && version_filters.opt_sort.is_none()
+            && !version_filters.reverse
+            && version_filters.opt_limit.is_none();
+
+        if is_empty {
+            return Ok(None);
+        }
+
+        Ok(Some(version_filters))
+    }
+
+    pub fn get_tree_diff_options(matches: &ArgMatches) -> HttmResult<TreeDiffOptions> {
+        let values: Vec<&OsStr> = matches
+            .values_of_os("INPUT_FILES")
+            .map(|values| values.collect())
+            .unwrap_or_default();
+
+        let (dir, snap_a, opt_snap_b) = match values.as_slice() {
+            [dir, snap_a] => (dir, snap_a, None),
+            [dir, snap_a, snap_b] if *snap_b == "live" => (dir, snap_a, None),
+            [dir, snap_a, snap_b] => (dir, snap_a, Some(snap_b.to_string_lossy().into_owned())),
+            _ => {
+                return Err(HttmError::new(
+                    "TREE_DIFF requires a directory and one or two snapshots, like: \"httm --tree-diff DIR SNAP_A [SNAP_B|live]\"",
+                )
+                .into())
+            }
+        };
+
+        // a directory may not exist live, if it was deleted, so we join with the pwd
+        let dir = match canonicalize(dir) {
+            Ok(path) => path,
+            Err(_) => Self::get_pwd()?.path_buf.join(dir),
+        };
+
+        Ok(TreeDiffOptions {
+            dir,
+            snap_a: snap_a.to_string_lossy().into_owned(),
+            opt_snap_b,
+        })
+    }
+
+    pub fn get_snap_filters(
+        values: &str,
+        select_mode: bool,
+        with_space: bool,
+    ) -> HttmResult<ListSnapsFilters> {
         let mut raw = values.trim_end().split(',');
 
         let omit_num_snaps = if let Some(value) = raw.next() {
@@ -993,9 +2170,24 @@ impl Config {
             select_mode,
             omit_num_snaps,
             name_filters,
+            with_space,
         })
     }
 
+    // a hold's tag may be followed by the same filters as LIST_SNAPS, like "incident,5,prep_Apt"
+    fn get_hold_tag_and_filters(value: &str) -> HttmResult<(String, Option<ListSnapsFilters>)> {
+        let (tag, opt_filters) = match value.split_once(',') {
+            Some((tag, filters)) => (tag, Some(Self::get_snap_filters(filters, false, false)?)),
+            None => (value, None),
+        };
+
+        if tag.is_empty() {
+            return Err(HttmError::new("A hold requires a non-empty tag. Quitting.").into());
+        }
+
+        Ok((tag.to_owned(), opt_filters))
+    }
+
     // use an associated function here because we may need this display again elsewhere
     pub fn generate_display_config(&self, paths_selected: &[PathData]) -> Self {
         // generate a config for a preview display only
@@ -1008,18 +2200,27 @@ impl Config {
             opt_no_traverse: false,
             opt_no_hidden: false,
             opt_json: false,
+            opt_delimited_format: None,
+            opt_format_template: None,
             opt_bulk_exclusion: None,
             opt_last_snap: None,
             opt_preview: None,
             opt_deleted_mode: None,
+            opt_deleted_when: false,
+            opt_detect_renames: false,
+            opt_snap_info: false,
+            opt_version_filters: self.opt_version_filters.clone(),
             uniqueness: ListSnapsOfType::UniqueMetadata,
             opt_omit_ditto: self.opt_omit_ditto,
             requested_utc_offset: self.requested_utc_offset,
+            requested_date_format: self.requested_date_format.clone(),
             exec_mode: ExecMode::Display,
             print_mode: PrintMode::FormattedDefault,
             dataset_collection: self.dataset_collection.clone(),
             pwd: self.pwd.clone(),
             opt_requested_dir: self.opt_requested_dir.clone(),
+            hooks: Hooks::default(),
+            opt_audit_log: None,
         }
     }
 }
@@ -19,7 +19,7 @@ use std::{
     cmp::{Ord, Ordering, PartialOrd},
     ffi::OsStr,
     fs::{symlink_metadata, DirEntry, File, FileType, Metadata},
-    io::BufReader,
+    io::{BufRead, BufReader},
     path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -35,13 +35,10 @@ use crate::{
     config::generate::PrintMode,
     library::{
         results::{HttmError, HttmResult},
-        utility::DateFormat,
+        utility::JsonDate,
     },
 };
-use crate::{
-    library::utility::{display_human_size, get_date},
-    GLOBAL_CONFIG,
-};
+use crate::{library::utility::display_human_size, GLOBAL_CONFIG};
 
 // only the most basic data from a DirEntry
 // for use to display in browse window and internally
@@ -230,19 +227,12 @@ impl Serialize for PathMetadata {
             PrintMode::RawNewline | PrintMode::RawZero
         ) {
             state.serialize_field("size", &self.size)?;
-            state.serialize_field("modify_time", &self.modify_time)?;
         } else {
-            let size = display_human_size(self.size);
-            let date = get_date(
-                GLOBAL_CONFIG.requested_utc_offset,
-                &self.modify_time,
-                DateFormat::Display,
-            );
-
-            state.serialize_field("size", &size)?;
-            state.serialize_field("modify_time", &date)?;
+            state.serialize_field("size", &display_human_size(self.size))?;
         }
 
+        state.serialize_field("modify_time", &JsonDate::new(&self.modify_time))?;
+
         state.end()
     }
 }
@@ -355,7 +345,7 @@ impl CompareVersionsContainer {
         false
     }
 
-    fn get_path_hash(path: &Path) -> HttmResult<u32> {
+    pub fn get_path_hash(path: &Path) -> HttmResult<u32> {
         const IN_BUFFER_SIZE: usize = 131_072;
 
         let self_file = File::open(path)?;
@@ -364,4 +354,30 @@ impl CompareVersionsContainer {
 
         adler32(&mut self_reader).map_err(|err| err.into())
     }
+
+    // unlike a hash, which may collide, a byte for byte comparison of two files
+    pub fn is_same_contents(path: &Path, other_path: &Path) -> HttmResult<bool> {
+        const IN_BUFFER_SIZE: usize = 131_072;
+
+        let mut self_reader = BufReader::with_capacity(IN_BUFFER_SIZE, File::open(path)?);
+        let mut other_reader = BufReader::with_capacity(IN_BUFFER_SIZE, File::open(other_path)?);
+
+        loop {
+            let self_buffer = self_reader.fill_buf()?;
+            let other_buffer = other_reader.fill_buf()?;
+
+            if self_buffer.is_empty() || other_buffer.is_empty() {
+                return Ok(self_buffer.is_empty() && other_buffer.is_empty());
+            }
+
+            let len = self_buffer.len().min(other_buffer.len());
+
+            if self_buffer[..len] != other_buffer[..len] {
+                return Ok(false);
+            }
+
+            self_reader.consume(len);
+            other_reader.consume(len);
+        }
+    }
 }
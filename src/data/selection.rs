@@ -24,6 +24,7 @@ use crate::data::paths::{BasicDirEntryInfo, PathData};
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
 use crate::library::results::HttmResult;
 use crate::library::utility::paint_string;
+use crate::lookup::deletion_window::DeletionWindow;
 use crate::{VersionsMap, GLOBAL_CONFIG};
 
 // these represent the items ready for selection and preview
@@ -33,10 +34,19 @@ use crate::{VersionsMap, GLOBAL_CONFIG};
 pub struct SelectionCandidate {
     path: PathBuf,
     file_type: Option<FileType>,
+    opt_deletion_window: Option<DeletionWindow>,
+    opt_renamed_to: Option<PathBuf>,
 }
 
 impl SelectionCandidate {
     pub fn new(basic_info: BasicDirEntryInfo, is_phantom: bool) -> Self {
+        // only phantom entries were deleted, so only they have a window in which they were deleted
+        let opt_deletion_window = if is_phantom && GLOBAL_CONFIG.opt_deleted_when {
+            DeletionWindow::new(&basic_info.path)
+        } else {
+            None
+        };
+
         SelectionCandidate {
             path: basic_info.path,
             // here save space of bool/padding instead of an "is_phantom: bool"
@@ -53,6 +63,18 @@ impl SelectionCandidate {
                     basic_info.file_type
                 }
             },
+            opt_deletion_window,
+            opt_renamed_to: None,
+        }
+    }
+
+    // a phantom entry which was not deleted, but renamed, see DETECT_RENAMES
+    pub fn renamed(basic_info: BasicDirEntryInfo, renamed_to: PathBuf) -> Self {
+        SelectionCandidate {
+            path: basic_info.path,
+            file_type: None,
+            opt_deletion_window: None,
+            opt_renamed_to: Some(renamed_to),
         }
     }
 
@@ -104,7 +126,17 @@ impl SkimItem for SelectionCandidate {
         self.path.to_string_lossy()
     }
     fn display(&self, _context: DisplayContext<'_>) -> AnsiString {
-        AnsiString::parse(&paint_string(self, &self.generate_display_name()))
+        let display_name = self.generate_display_name();
+        let painted = paint_string(self, &display_name);
+
+        match (&self.opt_renamed_to, &self.opt_deletion_window) {
+            (Some(renamed_to), _) => {
+                let new_name = renamed_to.file_name().unwrap_or_default().to_string_lossy();
+                AnsiString::parse(&format!("{painted}  (renamed to {new_name})"))
+            }
+            (None, Some(window)) => AnsiString::parse(&format!("{painted}  ({window})")),
+            (None, None) => AnsiString::parse(&painted),
+        }
     }
     fn output(&self) -> Cow<str> {
         self.text()
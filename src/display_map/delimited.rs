@@ -0,0 +1,311 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::borrow::Cow;
+use std::time::UNIX_EPOCH;
+
+use crate::config::generate::{BulkExclusion, DelimitedFormat, ExecMode, ListSnapsFilters};
+use crate::data::paths::{PathData, PathMetadata};
+use crate::display_versions::wrapper::VersionsDisplayWrapper;
+use crate::library::utility::get_date;
+use crate::lookup::file_mounts::MountsForFiles;
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+pub const DELIMITED_HEADER: [&str; 8] = [
+    "path",
+    "version_path",
+    "snapshot_name",
+    "dataset",
+    "size_bytes",
+    "mtime_epoch",
+    "mtime_formatted",
+    "is_live",
+];
+
+// one row per version, every mode which prints as delimited
+// values shares these same columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelimitedRow {
+    path: String,
+    version_path: String,
+    snapshot_name: String,
+    dataset: String,
+    opt_metadata: Option<PathMetadata>,
+    is_live: bool,
+}
+
+impl DelimitedRow {
+    fn from_snap_version(live_version: &PathData, snap_version: &PathData) -> Self {
+        let (snapshot_name, dataset) =
+            match DeconstructedSnapPathData::from_snap_mount(snap_version) {
+                Some(deconstructed) => (deconstructed.snap_name, deconstructed.dataset),
+                None => (String::new(), String::new()),
+            };
+
+        Self {
+            path: live_version.path_buf.to_string_lossy().into_owned(),
+            version_path: snap_version.path_buf.to_string_lossy().into_owned(),
+            snapshot_name,
+            dataset,
+            opt_metadata: snap_version.metadata,
+            is_live: false,
+        }
+    }
+
+    fn from_live_version(live_version: &PathData) -> Self {
+        let path = live_version.path_buf.to_string_lossy().into_owned();
+
+        Self {
+            version_path: path.clone(),
+            path,
+            snapshot_name: String::new(),
+            dataset: Self::get_live_dataset(live_version).unwrap_or_default(),
+            opt_metadata: live_version.metadata,
+            is_live: true,
+        }
+    }
+
+    fn from_mount(pathdata: &PathData, mount: &PathData) -> Self {
+        let dataset = GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_datasets
+            .get(&mount.path_buf)
+            .map(|md| md.source.clone())
+            .unwrap_or_default();
+
+        Self {
+            path: pathdata.path_buf.to_string_lossy().into_owned(),
+            version_path: mount.path_buf.to_string_lossy().into_owned(),
+            snapshot_name: String::new(),
+            dataset,
+            opt_metadata: pathdata.metadata,
+            is_live: true,
+        }
+    }
+
+    fn get_live_dataset(live_version: &PathData) -> Option<String> {
+        let map_of_datasets = &GLOBAL_CONFIG.dataset_collection.map_of_datasets;
+
+        let proximate_dataset_mount = match &GLOBAL_CONFIG.dataset_collection.opt_map_of_aliases {
+            Some(map_of_aliases) => match live_version.get_alias_dataset(map_of_aliases) {
+                Some(alias_snap_dir) => alias_snap_dir,
+                None => live_version.get_proximate_dataset(map_of_datasets).ok()?,
+            },
+            None => live_version.get_proximate_dataset(map_of_datasets).ok()?,
+        };
+
+        map_of_datasets
+            .get(proximate_dataset_mount)
+            .map(|md| md.source.clone())
+    }
+
+    fn fields(&self) -> [Cow<'_, str>; 8] {
+        // phantom files have no size or modify time, so leave these fields empty
+        let (size_bytes, mtime_epoch, mtime_formatted) = match &self.opt_metadata {
+            Some(md) => (
+                Cow::Owned(md.size.to_string()),
+                Cow::Owned(
+                    md.modify_time
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_secs())
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+                Cow::Owned(get_date(
+                    GLOBAL_CONFIG.requested_utc_offset,
+                    &md.modify_time,
+                    GLOBAL_CONFIG.requested_date_format.clone(),
+                )),
+            ),
+            None => (Cow::Borrowed(""), Cow::Borrowed(""), Cow::Borrowed("")),
+        };
+
+        [
+            Cow::Borrowed(self.path.as_str()),
+            Cow::Borrowed(self.version_path.as_str()),
+            Cow::Borrowed(self.snapshot_name.as_str()),
+            Cow::Borrowed(self.dataset.as_str()),
+            size_bytes,
+            mtime_epoch,
+            mtime_formatted,
+            Cow::Borrowed(if self.is_live { "true" } else { "false" }),
+        ]
+    }
+}
+
+#[derive(Debug)]
+pub struct PrintAsDelimited {
+    inner: Vec<DelimitedRow>,
+    delimited_format: DelimitedFormat,
+}
+
+impl<'a> From<&VersionsDisplayWrapper<'a>> for PrintAsDelimited {
+    fn from(versions_display_wrapper: &VersionsDisplayWrapper) -> Self {
+        let config = versions_display_wrapper.config;
+
+        let inner = versions_display_wrapper
+            .iter()
+            .filter(|(live_version, snaps)| match &config.exec_mode {
+                ExecMode::NumVersions(num_versions_mode) => {
+                    VersionsDisplayWrapper::is_num_versions_match(
+                        num_versions_mode,
+                        live_version,
+                        snaps,
+                    )
+                }
+                _ => true,
+            })
+            .flat_map(|(live_version, snaps)| {
+                // in num versions mode, a live version identical to the last snap is not a unique version,
+                // and last snap mode only ever prints snap versions
+                let include_live = match &config.exec_mode {
+                    ExecMode::NumVersions(_) => {
+                        live_version.metadata.is_some()
+                            && !VersionsMap::is_live_version_redundant(live_version, snaps)
+                    }
+                    _ => {
+                        config.opt_last_snap.is_none()
+                            && !matches!(config.opt_bulk_exclusion, Some(BulkExclusion::NoLive))
+                    }
+                };
+
+                let include_snaps =
+                    !matches!(config.opt_bulk_exclusion, Some(BulkExclusion::NoSnap));
+
+                snaps
+                    .iter()
+                    .filter(move |_snap_version| include_snaps)
+                    .map(|snap_version| DelimitedRow::from_snap_version(live_version, snap_version))
+                    .chain(include_live.then(|| DelimitedRow::from_live_version(live_version)))
+            })
+            .collect();
+
+        Self {
+            inner,
+            delimited_format: config.opt_delimited_format.unwrap_or(DelimitedFormat::Csv),
+        }
+    }
+}
+
+impl<'a> From<&MountsForFiles<'a>> for PrintAsDelimited {
+    fn from(mounts_for_files: &MountsForFiles) -> Self {
+        let inner = mounts_for_files
+            .iter()
+            .flat_map(|(pathdata, mounts)| {
+                mounts
+                    .iter()
+                    .map(|mount| DelimitedRow::from_mount(pathdata, mount))
+            })
+            .collect();
+
+        Self {
+            inner,
+            delimited_format: GLOBAL_CONFIG
+                .opt_delimited_format
+                .unwrap_or(DelimitedFormat::Csv),
+        }
+    }
+}
+
+impl PrintAsDelimited {
+    // the snap name map only contains ZFS snapshot names, so, here, rows are built from
+    // each version's snap mount, which includes btrfs and nilfs2 versions, and the
+    // same list snaps filters are applied to those rows
+    pub fn from_snap_versions(
+        versions_map: &VersionsMap,
+        opt_filters: &Option<ListSnapsFilters>,
+    ) -> Self {
+        let opt_name_filters = opt_filters
+            .as_ref()
+            .and_then(|filters| filters.name_filters.as_ref());
+        let omit_num_snaps = opt_filters
+            .as_ref()
+            .map(|filters| filters.omit_num_snaps)
+            .unwrap_or_default();
+
+        let inner = versions_map
+            .iter()
+            .flat_map(|(live_version, snaps)| {
+                let rows: Vec<DelimitedRow> = snaps
+                    .iter()
+                    .map(|snap_version| DelimitedRow::from_snap_version(live_version, snap_version))
+                    .filter(|row| match opt_name_filters {
+                        Some(names) => names
+                            .iter()
+                            .any(|pattern| row.snapshot_name.contains(pattern)),
+                        None => true,
+                    })
+                    .collect();
+
+                let num_rows = rows.len().saturating_sub(omit_num_snaps);
+
+                rows.into_iter().take(num_rows)
+            })
+            .collect();
+
+        Self {
+            inner,
+            delimited_format: GLOBAL_CONFIG
+                .opt_delimited_format
+                .unwrap_or(DelimitedFormat::Csv),
+        }
+    }
+
+    fn get_delimiter(&self) -> char {
+        match self.delimited_format {
+            DelimitedFormat::Csv => ',',
+            DelimitedFormat::Tsv => '\t',
+        }
+    }
+
+    // RFC 4180: fields containing the delimiter, a quotation mark or a line break
+    // are enclosed in quotation marks, and any quotation marks within are doubled
+    fn quote_field(field: &str, delimiter: char) -> Cow<'_, str> {
+        if field.contains([delimiter, '"', '\n', '\r']) {
+            Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+        } else {
+            Cow::Borrowed(field)
+        }
+    }
+
+    fn format_line<'b>(fields: impl Iterator<Item = Cow<'b, str>>, delimiter: char) -> String {
+        let mut line = fields
+            .map(|field| Self::quote_field(&field, delimiter).into_owned())
+            .collect::<Vec<String>>()
+            .join(&delimiter.to_string());
+
+        line.push('\n');
+        line
+    }
+}
+
+impl std::fmt::Display for PrintAsDelimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = self.get_delimiter();
+
+        let header = Self::format_line(DELIMITED_HEADER.into_iter().map(Cow::Borrowed), delimiter);
+
+        let output_buf = self.inner.iter().fold(header, |mut buffer, row| {
+            buffer += &Self::format_line(row.fields().into_iter(), delimiter);
+            buffer
+        });
+
+        write!(f, "{output_buf}")
+    }
+}
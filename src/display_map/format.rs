@@ -36,8 +36,20 @@ impl std::string::ToString for PrintAsMap {
                 }
                 ExecMode::NonInteractiveRecursive(_)
                 | ExecMode::RollForward(_)
+                | ExecMode::Diff(_)
+                | ExecMode::Changelog
+                | ExecMode::DirSummary
+                | ExecMode::TreeDiff(_)
+                | ExecMode::Grep(_)
+                | ExecMode::Find(_)
+                | ExecMode::ExportGit(_)
+                | ExecMode::Ounce(_)
+                | ExecMode::Watch(_)
+                | ExecMode::Prune(_)
+                | ExecMode::RecoverDeleted(_)
                 | ExecMode::NumVersions(_)
-                | ExecMode::Purge(_)
+                | ExecMode::Purge(..)
+                | ExecMode::Hold(..)
                 | ExecMode::SnapFileMount(_) => {
                     unreachable!(
                         "JSON print should not be available in the selected {:?} execution mode.",
@@ -55,10 +67,34 @@ impl std::string::ToString for PrintAsMap {
                 .flatten()
                 .map(|value| {
                     let delimiter = get_delimiter();
-                    format!("{value}{delimiter}")
+
+                    // raw space values are tab separated bytes, for use in scripts
+                    match self
+                        .opt_snap_space
+                        .as_ref()
+                        .and_then(|snap_space| snap_space.get(value))
+                    {
+                        Some(space) => format!(
+                            "{value}\t{}\t{}\t{}{delimiter}",
+                            space.used, space.written, space.referenced
+                        ),
+                        None => format!("{value}{delimiter}"),
+                    }
                 })
                 .collect::<String>(),
-            PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => self.format(),
+            PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
+                let mut buffer = self.format();
+
+                if let Some(summary) = self
+                    .opt_snap_space
+                    .as_ref()
+                    .and_then(|snap_space| snap_space.get_reclaimable_summary())
+                {
+                    buffer += &format!("\n{summary}\n");
+                }
+
+                buffer
+            }
         }
     }
 }
@@ -108,17 +144,20 @@ impl PrintAsMap {
                     .iter()
                     .enumerate()
                     .map(|(idx, value)| {
+                        let annotation = self.get_space_annotation(value).unwrap_or_default();
+
                         if matches!(&GLOBAL_CONFIG.print_mode, PrintMode::FormattedNotPretty) {
-                            format!("{NOT_SO_PRETTY_FIXED_WIDTH_PADDING}{value}")
+                            format!("{NOT_SO_PRETTY_FIXED_WIDTH_PADDING}{value}{annotation}")
                         } else if idx == 0 {
                             format!(
-                                "{:<width$} : \"{}\"\n",
+                                "{:<width$} : \"{}\"{}\n",
                                 display_path,
                                 value,
+                                annotation,
                                 width = padding
                             )
                         } else {
-                            format!("{:<padding$} : \"{value}\"\n", "")
+                            format!("{:<padding$} : \"{value}\"{annotation}\n", "")
                         }
                     })
                     .collect::<String>();
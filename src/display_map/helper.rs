@@ -21,8 +21,10 @@ use std::ops::Deref;
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 
-use crate::config::generate::MountDisplay;
+use crate::config::generate::{MountDisplay, PrintMode};
 use crate::display_versions::format::QUOTATION_MARKS_LEN;
+use crate::library::utility::display_human_size;
+use crate::lookup::snap_space::SnapSpaceMap;
 use crate::SnapNameMap;
 use crate::VersionsMap;
 use crate::{MountsForFiles, GLOBAL_CONFIG};
@@ -30,6 +32,7 @@ use crate::{MountsForFiles, GLOBAL_CONFIG};
 #[derive(Debug)]
 pub struct PrintAsMap {
     inner: BTreeMap<String, Vec<String>>,
+    pub opt_snap_space: Option<SnapSpaceMap>,
 }
 
 impl Deref for PrintAsMap {
@@ -42,7 +45,10 @@ impl Deref for PrintAsMap {
 
 impl From<BTreeMap<String, Vec<String>>> for PrintAsMap {
     fn from(map: BTreeMap<String, Vec<String>>) -> Self {
-        Self { inner: map }
+        Self {
+            inner: map,
+            opt_snap_space: None,
+        }
     }
 }
 
@@ -51,10 +57,32 @@ impl Serialize for PrintAsMap {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("PrintAsMap", 1)?;
+        match &self.opt_snap_space {
+            Some(snap_space) => {
+                let mut state = serializer.serialize_struct("PrintAsMap", 3)?;
 
-        state.serialize_field("inner", &self.inner)?;
-        state.end()
+                state.serialize_field("inner", &self.inner)?;
+                state.serialize_field("snapshot_space", snap_space.deref())?;
+
+                if matches!(
+                    GLOBAL_CONFIG.print_mode,
+                    PrintMode::RawNewline | PrintMode::RawZero
+                ) {
+                    state.serialize_field("reclaimable", &snap_space.opt_reclaimable)?;
+                } else {
+                    let opt_human_reclaimable = snap_space.opt_reclaimable.map(display_human_size);
+                    state.serialize_field("reclaimable", &opt_human_reclaimable)?;
+                }
+
+                state.end()
+            }
+            None => {
+                let mut state = serializer.serialize_struct("PrintAsMap", 1)?;
+
+                state.serialize_field("inner", &self.inner)?;
+                state.end()
+            }
+        }
     }
 }
 
@@ -84,7 +112,10 @@ impl<'a> From<&MountsForFiles<'a>> for PrintAsMap {
                 (key.path_buf.to_string_lossy().to_string(), res)
             })
             .collect();
-        Self { inner }
+        Self {
+            inner,
+            opt_snap_space: None,
+        }
     }
 }
 
@@ -100,7 +131,10 @@ impl From<&VersionsMap> for PrintAsMap {
                 (key.path_buf.to_string_lossy().to_string(), res)
             })
             .collect();
-        Self { inner }
+        Self {
+            inner,
+            opt_snap_space: None,
+        }
     }
 }
 
@@ -110,11 +144,26 @@ impl From<&SnapNameMap> for PrintAsMap {
             .iter()
             .map(|(key, value)| (key.path_buf.to_string_lossy().to_string(), value.clone()))
             .collect();
-        Self { inner }
+        Self {
+            inner,
+            opt_snap_space: None,
+        }
     }
 }
 
 impl PrintAsMap {
+    pub fn with_snap_space(mut self, snap_space: SnapSpaceMap) -> Self {
+        self.opt_snap_space = Some(snap_space);
+        self
+    }
+
+    pub fn get_space_annotation(&self, snap_name: &str) -> Option<String> {
+        self.opt_snap_space
+            .as_ref()
+            .and_then(|snap_space| snap_space.get(snap_name))
+            .map(|space| format!("  ({space})"))
+    }
+
     pub fn get_map_padding(&self) -> usize {
         self.inner.keys().max_by_key(|key| key.len()).map_or_else(
             || QUOTATION_MARKS_LEN,
@@ -23,7 +23,8 @@ use terminal_size::{terminal_size, Height, Width};
 use crate::config::generate::{BulkExclusion, Config, PrintMode};
 use crate::data::paths::{PathData, PHANTOM_DATE, PHANTOM_SIZE};
 use crate::library::utility::get_delimiter;
-use crate::library::utility::{display_human_size, get_date, paint_string, DateFormat};
+use crate::library::utility::{display_human_size, get_date, paint_string};
+use crate::lookup::snap_names::VersionSnapInfo;
 use crate::VersionsDisplayWrapper;
 // 2 space wide padding - used between date and size, and size and path
 pub const PRETTY_FIXED_WIDTH_PADDING: &str = "  ";
@@ -165,6 +166,16 @@ impl PathData {
         is_live_set: bool,
         padding_collection: &PaddingCollection,
     ) -> String {
+        // a user defined template replaces the ordinary layout entirely
+        if let Some(format_template) = &config.opt_format_template {
+            return format_template.format(
+                self,
+                config,
+                is_live_set,
+                &padding_collection.template_padding,
+            );
+        }
+
         // obtain metadata for timestamp and size
         let metadata = self.get_md_infallible();
 
@@ -217,24 +228,100 @@ impl PathData {
             };
 
         let display_date = if self.metadata.is_some() {
-            Cow::Owned(get_date(
+            let date = get_date(
                 config.requested_utc_offset,
                 &metadata.modify_time,
-                DateFormat::Display,
-            ))
+                config.requested_date_format.clone(),
+            );
+
+            // some date formats, like relative dates, vary in length
+            if matches!(&config.print_mode, PrintMode::FormattedNotPretty) {
+                Cow::Owned(date)
+            } else {
+                Cow::Owned(format!(
+                    "{:<width$}",
+                    date,
+                    width = padding_collection.date_padding_len
+                ))
+            }
         } else {
             Cow::Borrowed(&padding_collection.phantom_date_pad_str)
         };
 
+        let display_snap_info = if config.opt_snap_info {
+            self.format_snap_info(config, is_live_set, display_padding, padding_collection)
+        } else {
+            String::new()
+        };
+
+        format!(
+            "{}{}{}{}{}{}\n",
+            display_date,
+            display_padding,
+            display_size,
+            display_padding,
+            display_snap_info,
+            display_path
+        )
+    }
+
+    // the snapshot date, name and dataset columns, which are blank for live versions
+    fn format_snap_info(
+        &self,
+        config: &Config,
+        is_live_set: bool,
+        display_padding: &str,
+        padding_collection: &PaddingCollection,
+    ) -> String {
+        let opt_snap_info = if is_live_set {
+            None
+        } else {
+            VersionSnapInfo::new(self)
+        };
+
+        let (snap_date, snap_name, dataset) = match &opt_snap_info {
+            Some(snap_info) => (
+                snap_info
+                    .opt_snap_time
+                    .map(|snap_time| {
+                        get_date(
+                            config.requested_utc_offset,
+                            &snap_time,
+                            config.requested_date_format.clone(),
+                        )
+                    })
+                    .unwrap_or_default(),
+                snap_info.snap_name.as_str(),
+                snap_info.dataset.as_str(),
+            ),
+            None => (String::new(), "", ""),
+        };
+
+        if matches!(&config.print_mode, PrintMode::FormattedNotPretty) {
+            return format!(
+                "{snap_date}{display_padding}{snap_name}{display_padding}{dataset}{display_padding}"
+            );
+        }
+
         format!(
-            "{}{}{}{}{}\n",
-            display_date, display_padding, display_size, display_padding, display_path
+            "{:<date_width$}{display_padding}{:<name_width$}{display_padding}{:<dataset_width$}{display_padding}",
+            snap_date,
+            snap_name,
+            dataset,
+            date_width = padding_collection.snap_date_padding_len,
+            name_width = padding_collection.snap_name_padding_len,
+            dataset_width = padding_collection.dataset_padding_len,
         )
     }
 }
 
 pub struct PaddingCollection {
     pub size_padding_len: usize,
+    pub date_padding_len: usize,
+    pub snap_date_padding_len: usize,
+    pub snap_name_padding_len: usize,
+    pub dataset_padding_len: usize,
+    pub template_padding: Vec<usize>,
     pub fancy_border_string: String,
     pub phantom_date_pad_str: String,
     pub phantom_size_pad_str: String,
@@ -242,53 +329,106 @@ pub struct PaddingCollection {
 
 impl PaddingCollection {
     pub fn new(config: &Config, display_set: &DisplaySet) -> PaddingCollection {
+        // the blank date of a phantom version is also padded to the widest date
+        let phantom_date_len = get_date(
+            config.requested_utc_offset,
+            &PHANTOM_DATE,
+            config.requested_date_format.clone(),
+        )
+        .len();
+
         // calculate padding and borders for display later
-        let (size_padding_len, fancy_border_len) = display_set.iter().flatten().fold(
-            (0usize, 0usize),
-            |(mut size_padding_len, mut fancy_border_len), pathdata| {
-                let metadata = pathdata.get_md_infallible();
-
-                let (display_date, display_size, display_path) = {
-                    let date = get_date(
-                        config.requested_utc_offset,
-                        &metadata.modify_time,
-                        DateFormat::Display,
-                    );
-                    let size = format!(
-                        "{:>width$}",
-                        display_human_size(metadata.size),
-                        width = size_padding_len
-                    );
-                    let path = pathdata.path_buf.to_string_lossy();
+        let (size_padding_len, date_padding_len, fancy_border_len) =
+            display_set.iter().flatten().fold(
+                (0usize, phantom_date_len, 0usize),
+                |(mut size_padding_len, mut date_padding_len, mut fancy_border_len), pathdata| {
+                    let metadata = pathdata.get_md_infallible();
+
+                    let (display_date, display_size, display_path) = {
+                        let date = get_date(
+                            config.requested_utc_offset,
+                            &metadata.modify_time,
+                            config.requested_date_format.clone(),
+                        );
+                        let size = format!(
+                            "{:>width$}",
+                            display_human_size(metadata.size),
+                            width = size_padding_len
+                        );
+                        let path = pathdata.path_buf.to_string_lossy();
+
+                        (date, size, path)
+                    };
 
-                    (date, size, path)
-                };
+                    let display_size_len = display_human_size(metadata.size).len();
+                    // each date is padded to the widest, so the date is added to the line below
+                    let formatted_line_len = display_size.len()
+                        + display_path.len()
+                        + PRETTY_FIXED_WIDTH_PADDING_LEN_X2
+                        + QUOTATION_MARKS_LEN;
+
+                    size_padding_len = display_size_len.max(size_padding_len);
+                    date_padding_len = display_date.len().max(date_padding_len);
+                    fancy_border_len = formatted_line_len.max(fancy_border_len);
+                    (size_padding_len, date_padding_len, fancy_border_len)
+                },
+            );
+
+        let fancy_border_len = date_padding_len + fancy_border_len;
+
+        // only snap versions have snap info, so only snap versions are measured
+        let (snap_date_padding_len, snap_name_padding_len, dataset_padding_len) =
+            if config.opt_snap_info {
+                display_set[0]
+                    .iter()
+                    .filter_map(|pathdata| VersionSnapInfo::new(pathdata))
+                    .fold(
+                        (0usize, 0usize, 0usize),
+                        |(snap_date_len, snap_name_len, dataset_len), snap_info| {
+                            let snap_date = snap_info.opt_snap_time.map(|snap_time| {
+                                get_date(
+                                    config.requested_utc_offset,
+                                    &snap_time,
+                                    config.requested_date_format.clone(),
+                                )
+                            });
+
+                            (
+                                snap_date
+                                    .map_or(0, |snap_date| snap_date.len())
+                                    .max(snap_date_len),
+                                snap_info.snap_name.len().max(snap_name_len),
+                                snap_info.dataset.len().max(dataset_len),
+                            )
+                        },
+                    )
+            } else {
+                (0usize, 0usize, 0usize)
+            };
 
-                let display_size_len = display_human_size(metadata.size).len();
-                let formatted_line_len = display_date.len()
-                    + display_size.len()
-                    + display_path.len()
-                    + PRETTY_FIXED_WIDTH_PADDING_LEN_X2
-                    + QUOTATION_MARKS_LEN;
-
-                size_padding_len = display_size_len.max(size_padding_len);
-                fancy_border_len = formatted_line_len.max(fancy_border_len);
-                (size_padding_len, fancy_border_len)
-            },
-        );
+        let fancy_border_len = if config.opt_snap_info {
+            fancy_border_len
+                + snap_date_padding_len
+                + snap_name_padding_len
+                + dataset_padding_len
+                + PRETTY_FIXED_WIDTH_PADDING.len() * 3
+        } else {
+            fancy_border_len
+        };
+
+        // with a user defined template, padding is per placeholder, and every line is the same length
+        let (template_padding, fancy_border_len) = match &config.opt_format_template {
+            Some(format_template) => {
+                let template_padding = format_template.get_padding(config, display_set);
+                let line_len = format_template.get_line_len(&template_padding);
+                (template_padding, line_len)
+            }
+            None => (Vec::new(), fancy_border_len),
+        };
 
         let fancy_border_string: String = Self::get_fancy_border_string(fancy_border_len);
 
-        let phantom_date_pad_str = format!(
-            "{:<width$}",
-            "",
-            width = get_date(
-                config.requested_utc_offset,
-                &PHANTOM_DATE,
-                DateFormat::Display
-            )
-            .len()
-        );
+        let phantom_date_pad_str = format!("{:<date_padding_len$}", "");
         let phantom_size_pad_str = format!(
             "{:<width$}",
             "",
@@ -297,6 +437,11 @@ impl PaddingCollection {
 
         PaddingCollection {
             size_padding_len,
+            date_padding_len,
+            snap_date_padding_len,
+            snap_name_padding_len,
+            dataset_padding_len,
+            template_padding,
             fancy_border_string,
             phantom_date_pad_str,
             phantom_size_pad_str,
@@ -15,12 +15,24 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
+use std::time::SystemTime;
+
+use terminal_size::{terminal_size, Width};
+
 use crate::config::generate::NumVersionsMode;
 use crate::data::paths::PathData;
 use crate::display_map::helper::PrintAsMap;
+use crate::display_versions::timeline::Timeline;
 use crate::lookup::versions::VersionsMap;
 use crate::VersionsDisplayWrapper;
 
+// the width of a timeline, when the terminal width is unknown
+const DEFAULT_TIMELINE_WIDTH: usize = 60;
+const MIN_TIMELINE_WIDTH: usize = 10;
+const MAX_TIMELINE_WIDTH: usize = 100;
+// the width of the " : " before, "|" around, and "  NNN versions" after a timeline
+const TIMELINE_DECORATION_LEN: usize = 21;
+
 impl<'a> VersionsDisplayWrapper<'a> {
     pub fn format_as_num_versions(&self, num_versions_mode: &NumVersionsMode) -> String {
         // let delimiter = get_delimiter(config);
@@ -30,6 +42,10 @@ impl<'a> VersionsDisplayWrapper<'a> {
 
         let map_padding = printable_map.get_map_padding();
 
+        if matches!(num_versions_mode, NumVersionsMode::AllGraph) {
+            return self.format_as_timeline(map_padding);
+        }
+
         let write_out_buffer: String = self
             .iter()
             .filter_map(|(live_version, snaps)| {
@@ -84,17 +100,7 @@ impl<'a> VersionsDisplayWrapper<'a> {
 
         match num_versions_mode {
             NumVersionsMode::AllGraph => {
-                if !VersionsMap::is_live_version_redundant(live_version, snaps) {
-                    num_versions += 1
-                };
-
-                return Some(format!(
-                    "{:<width$} : {:*<num_versions$}{}",
-                    display_path,
-                    "",
-                    delimiter,
-                    width = padding
-                ));
+                unreachable!("graph mode is handled by format_as_timeline")
             }
             NumVersionsMode::AllNumerals => {
                 if !VersionsMap::is_live_version_redundant(live_version, snaps) {
@@ -118,40 +124,110 @@ impl<'a> VersionsDisplayWrapper<'a> {
                     ))
                 }
             }
-            NumVersionsMode::Multiple => {
-                if num_versions == 0
-                    || (num_versions == 1
-                        && VersionsMap::is_live_version_redundant(live_version, snaps))
-                {
-                    None
-                } else {
-                    Some(format!("{display_path}{delimiter}"))
-                }
-            }
-            NumVersionsMode::SingleAll => {
-                if num_versions == 0
-                    || (num_versions == 1
-                        && VersionsMap::is_live_version_redundant(live_version, snaps))
-                {
+            NumVersionsMode::Multiple
+            | NumVersionsMode::SingleAll
+            | NumVersionsMode::SingleNoSnap
+            | NumVersionsMode::SingleWithSnap => {
+                if Self::is_num_versions_match(num_versions_mode, live_version, snaps) {
                     Some(format!("{display_path}{delimiter}"))
                 } else {
                     None
                 }
             }
-            NumVersionsMode::SingleNoSnap => {
-                if num_versions == 0 {
-                    Some(format!("{display_path}{delimiter}"))
-                } else {
-                    None
+        }
+    }
+
+    fn format_as_timeline(&self, padding: usize) -> String {
+        let versions: Vec<(&PathData, Vec<SystemTime>)> = self
+            .iter()
+            .filter_map(|(live_version, snaps)| {
+                if live_version.metadata.is_none() {
+                    eprintln!(
+                        "{:<width$} : Path does not exist.",
+                        live_version.path_buf.display(),
+                        width = padding
+                    );
+                    return None;
                 }
+
+                let mut times: Vec<SystemTime> = snaps
+                    .iter()
+                    .map(|snap_version| snap_version.get_md_infallible().modify_time)
+                    .collect();
+
+                if !VersionsMap::is_live_version_redundant(live_version, snaps) {
+                    times.push(live_version.get_md_infallible().modify_time)
+                };
+
+                Some((live_version, times))
+            })
+            .collect();
+
+        let max_width = match terminal_size() {
+            Some((Width(width), _)) => (width as usize)
+                .saturating_sub(padding + TIMELINE_DECORATION_LEN)
+                .clamp(MIN_TIMELINE_WIDTH, MAX_TIMELINE_WIDTH),
+            None => DEFAULT_TIMELINE_WIDTH,
+        };
+
+        let timeline = match Timeline::new(
+            versions
+                .iter()
+                .flat_map(|(_live_version, times)| times.iter().copied()),
+            self.config.requested_utc_offset,
+            max_width,
+        ) {
+            Some(timeline) => timeline,
+            None => return String::new(),
+        };
+
+        let mut write_out_buffer: String = versions
+            .iter()
+            .map(|(live_version, times)| {
+                let num_versions = match times.len() {
+                    1 => "1 version".to_owned(),
+                    num => format!("{num} versions"),
+                };
+
+                format!(
+                    "{:<width$} : {}  {}\n",
+                    live_version.path_buf.display(),
+                    timeline.render(times),
+                    num_versions,
+                    width = padding
+                )
+            })
+            .collect();
+
+        write_out_buffer += &timeline.legend();
+
+        write_out_buffer
+    }
+
+    // should this path be printed given the num versions mode requested?
+    // AllNumerals and AllGraph modes print every path
+    pub fn is_num_versions_match(
+        num_versions_mode: &NumVersionsMode,
+        live_version: &PathData,
+        snaps: &[PathData],
+    ) -> bool {
+        let num_versions = snaps.len();
+
+        match num_versions_mode {
+            NumVersionsMode::AllNumerals | NumVersionsMode::AllGraph => true,
+            NumVersionsMode::Multiple => {
+                !(num_versions == 0
+                    || (num_versions == 1
+                        && VersionsMap::is_live_version_redundant(live_version, snaps)))
             }
+            NumVersionsMode::SingleAll => {
+                num_versions == 0
+                    || (num_versions == 1
+                        && VersionsMap::is_live_version_redundant(live_version, snaps))
+            }
+            NumVersionsMode::SingleNoSnap => num_versions == 0,
             NumVersionsMode::SingleWithSnap => {
-                if num_versions == 1 && VersionsMap::is_live_version_redundant(live_version, snaps)
-                {
-                    Some(format!("{display_path}{delimiter}"))
-                } else {
-                    None
-                }
+                num_versions == 1 && VersionsMap::is_live_version_redundant(live_version, snaps)
             }
         }
     }
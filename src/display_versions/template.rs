@@ -0,0 +1,276 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::borrow::Cow;
+
+use crate::config::generate::{Config, PrintMode};
+use crate::data::paths::PathData;
+use crate::display_versions::format::DisplaySet;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{
+    display_human_size, get_date, paint_string, parse_template, strftime_to_format_description,
+    DateFormat, TemplateSegment,
+};
+use crate::lookup::deletion_window::SnapInstant;
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::GLOBAL_CONFIG;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplateField {
+    Path,
+    RelativePath,
+    Size(SizeRepr),
+    ModifyTime(DateFormat),
+    SnapName,
+    SnapTime(DateFormat),
+    Dataset,
+    FsType,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SizeRepr {
+    Human,
+    Bytes,
+}
+
+// a user defined layout for each version line, like:
+// "{mtime:%Y-%m-%d} {size:h} {snap_name} {path}"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatTemplate {
+    segments: Vec<TemplateSegment<TemplateField>>,
+}
+
+impl FormatTemplate {
+    pub fn new(raw: &str) -> HttmResult<Self> {
+        let segments = parse_template(raw, "FORMAT_TEMPLATE", Self::parse_placeholder)?;
+
+        if !segments
+            .iter()
+            .any(|segment| matches!(segment, TemplateSegment::Placeholder(_)))
+        {
+            return Err(
+                HttmError::new("FORMAT_TEMPLATE must contain at least one placeholder.").into(),
+            );
+        }
+
+        Ok(Self { segments })
+    }
+
+    fn parse_placeholder(placeholder: &str) -> HttmResult<TemplateField> {
+        let (name, opt_spec) = match placeholder.split_once(':') {
+            Some((name, spec)) => (name.trim(), Some(spec)),
+            None => (placeholder.trim(), None),
+        };
+
+        let field = match (name, opt_spec) {
+            ("path", None) => TemplateField::Path,
+            ("relative_path" | "relpath", None) => TemplateField::RelativePath,
+            ("size", None | Some("h")) => TemplateField::Size(SizeRepr::Human),
+            ("size", Some("b")) => TemplateField::Size(SizeRepr::Bytes),
+            ("mtime" | "modify_time", None) => TemplateField::ModifyTime(DateFormat::Display),
+            ("mtime" | "modify_time", Some(strftime)) => TemplateField::ModifyTime(
+                DateFormat::Custom(strftime_to_format_description(strftime)?),
+            ),
+            ("snap_name" | "snapshot_name", None) => TemplateField::SnapName,
+            ("snap_time" | "snapshot_time", None) => TemplateField::SnapTime(DateFormat::Display),
+            ("snap_time" | "snapshot_time", Some(strftime)) => TemplateField::SnapTime(
+                DateFormat::Custom(strftime_to_format_description(strftime)?),
+            ),
+            ("dataset", None) => TemplateField::Dataset,
+            ("fs_type" | "filesystem_type", None) => TemplateField::FsType,
+            _ => {
+                let msg = format!(
+                    "FORMAT_TEMPLATE contains an unknown placeholder or specifier: \"{{{placeholder}}}\""
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+        };
+
+        Ok(field)
+    }
+
+    fn placeholders(&self) -> impl Iterator<Item = &TemplateField> {
+        self.segments.iter().filter_map(|segment| match segment {
+            TemplateSegment::Placeholder(field) => Some(field),
+            TemplateSegment::Literal(_) => None,
+        })
+    }
+
+    // one value for each placeholder, in order
+    fn get_values(&self, pathdata: &PathData, config: &Config) -> Vec<String> {
+        // only snap versions will deconstruct, live versions take their info from the live dataset
+        let opt_deconstructed = DeconstructedSnapPathData::from_snap_mount(pathdata);
+
+        self.placeholders()
+            .map(|field| match field {
+                TemplateField::Path => pathdata.path_buf.to_string_lossy().into_owned(),
+                TemplateField::RelativePath => match &opt_deconstructed {
+                    Some(deconstructed) => deconstructed
+                        .relpath
+                        .as_ref()
+                        .map(|relpath| relpath.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    None => pathdata
+                        .get_proximate_dataset(&GLOBAL_CONFIG.dataset_collection.map_of_datasets)
+                        .and_then(|mount| pathdata.get_relative_path(mount))
+                        .map(|relpath| relpath.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                },
+                // phantom files have no size or modify time
+                TemplateField::Size(size_repr) => match (&pathdata.metadata, size_repr) {
+                    (Some(md), SizeRepr::Human) => display_human_size(md.size),
+                    (Some(md), SizeRepr::Bytes) => md.size.to_string(),
+                    (None, _) => String::new(),
+                },
+                TemplateField::ModifyTime(date_format) => match &pathdata.metadata {
+                    Some(md) => get_date(
+                        config.requested_utc_offset,
+                        &md.modify_time,
+                        Self::get_date_format(date_format, config),
+                    ),
+                    None => String::new(),
+                },
+                TemplateField::SnapName => opt_deconstructed
+                    .as_ref()
+                    .map(|deconstructed| deconstructed.snap_name.clone())
+                    .unwrap_or_default(),
+                // live versions are on no snapshot, so have no snapshot time
+                TemplateField::SnapTime(date_format) => match &opt_deconstructed {
+                    Some(_deconstructed) => SnapInstant::from_version(pathdata)
+                        .map(|snap_instant| {
+                            get_date(
+                                config.requested_utc_offset,
+                                &snap_instant.time,
+                                Self::get_date_format(date_format, config),
+                            )
+                        })
+                        .unwrap_or_default(),
+                    None => String::new(),
+                },
+                TemplateField::Dataset => match &opt_deconstructed {
+                    Some(deconstructed) => deconstructed.dataset.clone(),
+                    None => Self::get_live_dataset_md(pathdata)
+                        .map(|(source, _fs_type)| source)
+                        .unwrap_or_default(),
+                },
+                TemplateField::FsType => match &opt_deconstructed {
+                    Some(deconstructed) => deconstructed.fs_type.to_string(),
+                    None => Self::get_live_dataset_md(pathdata)
+                        .map(|(_source, fs_type)| fs_type)
+                        .unwrap_or_default(),
+                },
+            })
+            .collect()
+    }
+
+    // a placeholder without a date format uses the requested date format
+    fn get_date_format(date_format: &DateFormat, config: &Config) -> DateFormat {
+        match date_format {
+            DateFormat::Display => config.requested_date_format.clone(),
+            _ => date_format.clone(),
+        }
+    }
+
+    fn get_live_dataset_md(pathdata: &PathData) -> Option<(String, String)> {
+        let map_of_datasets = &GLOBAL_CONFIG.dataset_collection.map_of_datasets;
+
+        pathdata
+            .get_proximate_dataset(map_of_datasets)
+            .ok()
+            .and_then(|mount| map_of_datasets.get(mount))
+            .map(|md| (md.source.clone(), md.fs_type.to_string()))
+    }
+
+    // the widest value for each placeholder across the display set, so columns line up
+    pub fn get_padding(&self, config: &Config, display_set: &DisplaySet) -> Vec<usize> {
+        display_set.iter().flatten().fold(
+            vec![0usize; self.placeholders().count()],
+            |mut padding, pathdata| {
+                self.get_values(pathdata, config)
+                    .iter()
+                    .zip(padding.iter_mut())
+                    .for_each(|(value, width)| *width = value.chars().count().max(*width));
+                padding
+            },
+        )
+    }
+
+    pub fn get_line_len(&self, padding: &[usize]) -> usize {
+        let literals_len: usize = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                TemplateSegment::Literal(literal) => literal.chars().count(),
+                TemplateSegment::Placeholder(_) => 0,
+            })
+            .sum();
+
+        literals_len + padding.iter().sum::<usize>()
+    }
+
+    pub fn format(
+        &self,
+        pathdata: &PathData,
+        config: &Config,
+        is_live_set: bool,
+        padding: &[usize],
+    ) -> String {
+        let is_pretty = !matches!(config.print_mode, PrintMode::FormattedNotPretty);
+        let mut values = self.get_values(pathdata, config).into_iter();
+        let mut widths = padding.iter();
+        let last_idx = self.segments.len() - 1;
+
+        let mut line: String = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(idx, segment)| match segment {
+                TemplateSegment::Literal(literal) => Cow::Borrowed(literal.as_str()),
+                TemplateSegment::Placeholder(field) => {
+                    let value = values.next().unwrap_or_default();
+                    let width = widths.next().copied().unwrap_or_default();
+
+                    if !is_pretty {
+                        return Cow::Owned(value);
+                    }
+
+                    let fill = " ".repeat(width.saturating_sub(value.chars().count()));
+
+                    match field {
+                        // sizes are right aligned, like the ordinary display
+                        TemplateField::Size(_) => Cow::Owned(fill + &value),
+                        // paint the live paths with ls colors, as in the ordinary display
+                        TemplateField::Path | TemplateField::RelativePath if is_live_set => {
+                            let painted = paint_string(pathdata, &value).into_owned();
+                            // no need to pad the last value on the line
+                            if idx == last_idx {
+                                Cow::Owned(painted)
+                            } else {
+                                Cow::Owned(painted + &fill)
+                            }
+                        }
+                        _ if idx == last_idx => Cow::Owned(value),
+                        _ => Cow::Owned(value + &fill),
+                    }
+                }
+            })
+            .collect();
+
+        line.push('\n');
+        line
+    }
+}
@@ -0,0 +1,264 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::time::SystemTime;
+
+use time::{OffsetDateTime, Time, UtcOffset};
+
+use crate::library::utility::{get_date, DateFormat};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const DAYS_PER_WEEK: i64 = 7;
+const AXIS_DATE_FORMAT: &str = "[year]-[month]-[day]";
+
+// the span of time represented by each column of a timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Granularity {
+    Days(i64),
+    Month,
+}
+
+// a horizontal axis, shared by every file, from the oldest to the newest version displayed,
+// in which each column counts the versions whose modify times fall within a day, a week, or a month
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    utc_offset: UtcOffset,
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+    granularity: Granularity,
+    num_columns: usize,
+}
+
+impl Timeline {
+    // uses the finest granularity for which the whole range fits within max_width columns
+    pub fn new<I>(times: I, utc_offset: UtcOffset, max_width: usize) -> Option<Self>
+    where
+        I: Iterator<Item = SystemTime> + Clone,
+    {
+        let oldest = times.clone().min()?;
+        let newest = times.max()?;
+
+        // columns begin at midnight, in the requested UTC offset
+        let start = OffsetDateTime::from(oldest)
+            .to_offset(utc_offset)
+            .replace_time(Time::MIDNIGHT);
+        let end = OffsetDateTime::from(newest).to_offset(utc_offset);
+
+        let max_width = max_width.max(1) as i64;
+        let num_days = Self::get_day_index(start, end) + 1;
+        let num_weeks = (num_days + DAYS_PER_WEEK - 1) / DAYS_PER_WEEK;
+        let num_months = Self::get_month_index(start, end) + 1;
+
+        let (granularity, num_columns) = if num_days <= max_width {
+            (Granularity::Days(1), num_days)
+        } else if num_weeks <= max_width {
+            (Granularity::Days(DAYS_PER_WEEK), num_weeks)
+        } else if num_months <= max_width {
+            (Granularity::Month, num_months)
+        } else {
+            // a very long history is divided evenly
+            let days_per_column = (num_days + max_width - 1) / max_width;
+            (
+                Granularity::Days(days_per_column),
+                (num_days + days_per_column - 1) / days_per_column,
+            )
+        };
+
+        Some(Self {
+            utc_offset,
+            start,
+            end,
+            granularity,
+            num_columns: num_columns as usize,
+        })
+    }
+
+    fn get_day_index(start: OffsetDateTime, time: OffsetDateTime) -> i64 {
+        (time - start).whole_seconds().div_euclid(SECONDS_PER_DAY)
+    }
+
+    fn get_month_index(start: OffsetDateTime, time: OffsetDateTime) -> i64 {
+        let months =
+            |date_time: OffsetDateTime| date_time.year() as i64 * 12 + date_time.month() as i64;
+
+        months(time) - months(start)
+    }
+
+    fn get_column(&self, time: SystemTime) -> usize {
+        let time = OffsetDateTime::from(time).to_offset(self.utc_offset);
+
+        let index = match self.granularity {
+            Granularity::Days(days_per_column) => {
+                Self::get_day_index(self.start, time).div_euclid(days_per_column)
+            }
+            Granularity::Month => Self::get_month_index(self.start, time),
+        };
+
+        (index.max(0) as usize).min(self.num_columns - 1)
+    }
+
+    // a row marks each column with the number of versions within it
+    pub fn render(&self, times: &[SystemTime]) -> String {
+        let mut counts = vec![0usize; self.num_columns];
+
+        times
+            .iter()
+            .for_each(|time| counts[self.get_column(*time)] += 1);
+
+        let row: String = counts
+            .iter()
+            .map(|count| match count {
+                0 => '-',
+                1 => '*',
+                2..=9 => char::from_digit(*count as u32, 10).unwrap_or('#'),
+                _ => '#',
+            })
+            .collect();
+
+        format!("|{row}|")
+    }
+
+    pub fn legend(&self) -> String {
+        let date_format = || DateFormat::Custom(AXIS_DATE_FORMAT.to_owned());
+
+        let column = match self.granularity {
+            Granularity::Days(1) => "a day".to_owned(),
+            Granularity::Days(DAYS_PER_WEEK) => "a week".to_owned(),
+            Granularity::Days(days) => format!("{days} days"),
+            Granularity::Month => "a month".to_owned(),
+        };
+
+        format!(
+            "\nTimeline from {} to {}, in which each column is {column}.\n\
+            Legend: \"*\" one version, \"2\"-\"9\" the number of versions, \"#\" ten or more versions, \"-\" no versions.\n",
+            get_date(self.utc_offset, &self.start.into(), date_format()),
+            get_date(self.utc_offset, &self.end.into(), date_format()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::format_description::well_known::Rfc3339;
+
+    fn at(raw: &str) -> SystemTime {
+        OffsetDateTime::parse(raw, &Rfc3339).unwrap().into()
+    }
+
+    fn new_timeline(raw_times: &[&str], utc_offset: UtcOffset, max_width: usize) -> Timeline {
+        let times: Vec<SystemTime> = raw_times.iter().map(|raw| at(raw)).collect();
+
+        Timeline::new(times.into_iter(), utc_offset, max_width).unwrap()
+    }
+
+    #[test]
+    fn days_until_they_no_longer_fit() {
+        // ten days
+        let raw_times = ["2023-01-01T00:00:00Z", "2023-01-10T12:00:00Z"];
+
+        let timeline = new_timeline(&raw_times, UtcOffset::UTC, 10);
+        assert_eq!(timeline.granularity, Granularity::Days(1));
+        assert_eq!(timeline.num_columns, 10);
+
+        let timeline = new_timeline(&raw_times, UtcOffset::UTC, 9);
+        assert_eq!(timeline.granularity, Granularity::Days(DAYS_PER_WEEK));
+        assert_eq!(timeline.num_columns, 2);
+    }
+
+    #[test]
+    fn weeks_until_they_no_longer_fit() {
+        // seventy days, or ten weeks, across three months
+        let raw_times = ["2023-01-01T00:00:00Z", "2023-03-11T12:00:00Z"];
+
+        let timeline = new_timeline(&raw_times, UtcOffset::UTC, 10);
+        assert_eq!(timeline.granularity, Granularity::Days(DAYS_PER_WEEK));
+        assert_eq!(timeline.num_columns, 10);
+
+        let timeline = new_timeline(&raw_times, UtcOffset::UTC, 9);
+        assert_eq!(timeline.granularity, Granularity::Month);
+        assert_eq!(timeline.num_columns, 3);
+    }
+
+    #[test]
+    fn months_until_they_no_longer_fit() {
+        // 365 days, across twelve months
+        let raw_times = ["2023-01-01T00:00:00Z", "2023-12-31T12:00:00Z"];
+
+        let timeline = new_timeline(&raw_times, UtcOffset::UTC, 12);
+        assert_eq!(timeline.granularity, Granularity::Month);
+        assert_eq!(timeline.num_columns, 12);
+
+        let timeline = new_timeline(&raw_times, UtcOffset::UTC, 11);
+        assert_eq!(timeline.granularity, Granularity::Days(34));
+        assert_eq!(timeline.num_columns, 11);
+        assert_eq!(timeline.get_column(at("2023-12-31T12:00:00Z")), 10);
+    }
+
+    #[test]
+    fn single_version() {
+        let timeline = new_timeline(&["2023-01-01T12:00:00Z"], UtcOffset::UTC, 80);
+
+        assert_eq!(timeline.granularity, Granularity::Days(1));
+        assert_eq!(timeline.num_columns, 1);
+        assert_eq!(timeline.get_column(at("2023-01-01T12:00:00Z")), 0);
+        assert_eq!(timeline.render(&[at("2023-01-01T12:00:00Z")]), "|*|");
+    }
+
+    #[test]
+    fn columns_begin_at_midnight_in_the_requested_offset() {
+        let eastern = UtcOffset::from_hms(-5, 0, 0).unwrap();
+
+        // 23:30 and 00:30 in the eastern offset, but the same day in UTC
+        let before_midnight = at("2023-01-01T04:30:00Z");
+        let after_midnight = at("2023-01-01T05:30:00Z");
+
+        let timeline = new_timeline(
+            &["2023-01-01T04:30:00Z", "2023-01-01T05:30:00Z"],
+            eastern,
+            80,
+        );
+        assert_eq!(timeline.num_columns, 2);
+        assert_eq!(timeline.get_column(before_midnight), 0);
+        assert_eq!(timeline.get_column(after_midnight), 1);
+
+        let timeline = new_timeline(
+            &["2023-01-01T04:30:00Z", "2023-01-01T05:30:00Z"],
+            UtcOffset::UTC,
+            80,
+        );
+        assert_eq!(timeline.num_columns, 1);
+        assert_eq!(timeline.get_column(before_midnight), 0);
+        assert_eq!(timeline.get_column(after_midnight), 0);
+    }
+
+    #[test]
+    fn months_begin_at_midnight_in_the_requested_offset() {
+        let eastern = UtcOffset::from_hms(-5, 0, 0).unwrap();
+
+        let timeline = new_timeline(
+            &["2023-01-01T00:00:00-05:00", "2023-06-01T00:00:00-05:00"],
+            eastern,
+            6,
+        );
+        assert_eq!(timeline.granularity, Granularity::Month);
+        assert_eq!(timeline.num_columns, 6);
+
+        // already February in UTC
+        assert_eq!(timeline.get_column(at("2023-01-31T23:30:00-05:00")), 0);
+        assert_eq!(timeline.get_column(at("2023-02-01T00:30:00-05:00")), 1);
+    }
+}
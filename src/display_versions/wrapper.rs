@@ -22,8 +22,10 @@ use serde::{Serialize, Serializer};
 
 use crate::config::generate::{Config, ExecMode, PrintMode};
 use crate::data::paths::PathData;
+use crate::display_map::delimited::PrintAsDelimited;
 use crate::display_map::helper::PrintAsMap;
-use crate::library::utility::get_delimiter;
+use crate::library::utility::{get_delimiter, JsonDate};
+use crate::lookup::snap_names::VersionSnapInfo;
 use crate::lookup::versions::VersionsMap;
 
 pub struct VersionsDisplayWrapper<'a> {
@@ -33,6 +35,10 @@ pub struct VersionsDisplayWrapper<'a> {
 
 impl<'a> std::string::ToString for VersionsDisplayWrapper<'a> {
     fn to_string(&self) -> String {
+        if self.config.opt_delimited_format.is_some() {
+            return PrintAsDelimited::from(self).to_string();
+        }
+
         match &self.config.exec_mode {
             ExecMode::NumVersions(num_versions_mode) => {
                 self.format_as_num_versions(num_versions_mode)
@@ -95,6 +101,30 @@ impl<'a> Serialize for VersionsDisplayWrapper<'a> {
         // 3 is the number of fields in the struct.
         let mut state = serializer.serialize_struct("VersionMap", 1)?;
 
+        if self.config.opt_snap_info {
+            let new_map: BTreeMap<String, Vec<VersionWithSnapInfo>> = self
+                .deref()
+                .iter()
+                .map(|(key, values)| {
+                    let new_values = values
+                        .iter()
+                        .map(|snap_version| VersionWithSnapInfo {
+                            pathdata: snap_version,
+                            opt_snap_info: VersionSnapInfo::new(snap_version),
+                        })
+                        .chain(std::iter::once(VersionWithSnapInfo {
+                            pathdata: key,
+                            opt_snap_info: None,
+                        }))
+                        .collect();
+                    (key.path_buf.to_string_lossy().to_string(), new_values)
+                })
+                .collect();
+
+            state.serialize_field("versions", &new_map)?;
+            return state.end();
+        }
+
         let new_map: BTreeMap<String, Vec<PathData>> = self
             .deref()
             .iter()
@@ -109,3 +139,47 @@ impl<'a> Serialize for VersionsDisplayWrapper<'a> {
         state.end()
     }
 }
+
+// a version, with the snapshot on which it resides, None for a live version
+struct VersionWithSnapInfo<'a> {
+    pathdata: &'a PathData,
+    opt_snap_info: Option<VersionSnapInfo>,
+}
+
+impl<'a> Serialize for VersionWithSnapInfo<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("VersionWithSnapInfo", 5)?;
+
+        let opt_snap_time = self
+            .opt_snap_info
+            .as_ref()
+            .and_then(|snap_info| snap_info.opt_snap_time);
+
+        state.serialize_field("path", &self.pathdata.path_buf)?;
+        state.serialize_field("metadata", &self.pathdata.metadata)?;
+        state.serialize_field(
+            "snapshot_name",
+            &self
+                .opt_snap_info
+                .as_ref()
+                .map(|snap_info| &snap_info.snap_name),
+        )?;
+
+        state.serialize_field(
+            "snapshot_time",
+            &opt_snap_time.map(|snap_time| JsonDate::new(&snap_time)),
+        )?;
+
+        state.serialize_field(
+            "dataset",
+            &self
+                .opt_snap_info
+                .as_ref()
+                .map(|snap_info| &snap_info.dataset),
+        )?;
+        state.end()
+    }
+}
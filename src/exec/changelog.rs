@@ -0,0 +1,275 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::collections::BTreeMap;
+use std::fs::read;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::config::generate::PrintMode;
+use crate::data::paths::PathData;
+use crate::exec::diff_versions::{DiffVersions, DEFAULT_CONTEXT_LINES};
+use crate::library::line_diff::{is_binary, LineDiff};
+use crate::library::results::HttmResult;
+use crate::library::utility::{
+    display_human_size, display_human_size_delta, get_date, get_delimiter, print_output_buf,
+};
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+enum ChangeType {
+    // the first version has nothing to compare against
+    Initial,
+    Identical,
+    Binary,
+    Modified(String),
+}
+
+// each unique version, compared to the unique version which came before it
+pub struct ChangelogEntry {
+    pathdata: PathData,
+    opt_snap_name: Option<String>,
+    is_live: bool,
+    opt_previous_size: Option<u64>,
+    change_type: ChangeType,
+}
+
+pub struct Changelog {
+    inner: BTreeMap<PathData, Vec<ChangelogEntry>>,
+}
+
+impl Changelog {
+    pub fn exec(versions_map: VersionsMap) -> HttmResult<()> {
+        // JSON patches should never contain color escape codes
+        let color = !GLOBAL_CONFIG.opt_json && DiffVersions::use_color();
+        let changelog = Self::new(versions_map, color)?;
+
+        let output_buf = if GLOBAL_CONFIG.opt_json {
+            changelog.to_json()
+        } else {
+            changelog.to_string()
+        };
+
+        print_output_buf(output_buf)
+    }
+
+    fn new(versions_map: VersionsMap, color: bool) -> HttmResult<Self> {
+        let inner = versions_map
+            .inner
+            .into_iter()
+            .map(|(live_version, snap_versions)| {
+                // the changelog always ends with the live version, if one exists
+                let mut versions = snap_versions;
+                if live_version.metadata.is_some() {
+                    versions.push(live_version.clone());
+                }
+
+                let entries = Self::get_entries(&live_version, &versions, color)?;
+
+                Ok((live_version, entries))
+            })
+            .collect::<HttmResult<BTreeMap<PathData, Vec<ChangelogEntry>>>>()?;
+
+        Ok(Self { inner })
+    }
+
+    fn get_entries(
+        live_version: &PathData,
+        versions: &[PathData],
+        color: bool,
+    ) -> HttmResult<Vec<ChangelogEntry>> {
+        let mut opt_previous: Option<(&PathData, Vec<u8>)> = None;
+
+        versions
+            .iter()
+            .map(|pathdata| {
+                let bytes = read(&pathdata.path_buf)?;
+                let is_live = pathdata == live_version;
+
+                let opt_snap_name = if is_live {
+                    None
+                } else {
+                    DeconstructedSnapPathData::from_snap_mount(pathdata)
+                        .map(|deconstructed| deconstructed.snap_name)
+                };
+
+                let change_type = match &opt_previous {
+                    Some((_previous, previous_bytes)) if previous_bytes == &bytes => {
+                        ChangeType::Identical
+                    }
+                    Some((_previous, previous_bytes))
+                        if is_binary(previous_bytes) || is_binary(&bytes) =>
+                    {
+                        ChangeType::Binary
+                    }
+                    Some((previous, previous_bytes)) => {
+                        let previous_text = String::from_utf8_lossy(previous_bytes);
+                        let text = String::from_utf8_lossy(&bytes);
+
+                        let patch = LineDiff::new(&previous_text, &text).unified(
+                            &DiffVersions::get_label(&previous.path_buf),
+                            &DiffVersions::get_label(&pathdata.path_buf),
+                            DEFAULT_CONTEXT_LINES,
+                            color,
+                        );
+
+                        ChangeType::Modified(patch)
+                    }
+                    None => ChangeType::Initial,
+                };
+
+                let opt_previous_size = opt_previous
+                    .as_ref()
+                    .map(|(_previous, previous_bytes)| previous_bytes.len() as u64);
+
+                opt_previous = Some((pathdata, bytes));
+
+                Ok(ChangelogEntry {
+                    pathdata: pathdata.clone(),
+                    opt_snap_name,
+                    is_live,
+                    opt_previous_size,
+                    change_type,
+                })
+            })
+            .collect()
+    }
+
+    fn to_json(&self) -> String {
+        let res = match GLOBAL_CONFIG.print_mode {
+            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                serde_json::to_string(self)
+            }
+            PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+        };
+
+        match res {
+            Ok(s) => {
+                let delimiter = get_delimiter();
+                format!("{s}{delimiter}")
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                std::process::exit(1)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Changelog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.iter().try_for_each(|(live_version, entries)| {
+            writeln!(f, "Changelog: {:?}", live_version.path_buf)?;
+
+            if entries.is_empty() {
+                writeln!(f, "No versions of this file could be found.")?;
+            }
+
+            entries.iter().try_for_each(|entry| {
+                let md = entry.pathdata.get_md_infallible();
+
+                let date = get_date(
+                    GLOBAL_CONFIG.requested_utc_offset,
+                    &md.modify_time,
+                    GLOBAL_CONFIG.requested_date_format.clone(),
+                );
+
+                let size = match entry.opt_previous_size {
+                    Some(previous_size) => format!(
+                        "{} ({})",
+                        display_human_size(md.size),
+                        display_human_size_delta(previous_size, md.size)
+                    ),
+                    None => display_human_size(md.size),
+                };
+
+                let version = match &entry.opt_snap_name {
+                    Some(snap_name) => snap_name.as_str(),
+                    None if entry.is_live => "live version",
+                    None => "snapshot version",
+                };
+
+                writeln!(f, "{:─<80}", "")?;
+                writeln!(f, "{date}  {size}  {version}")?;
+                writeln!(f, "{:?}", entry.pathdata.path_buf)?;
+
+                match &entry.change_type {
+                    ChangeType::Initial => writeln!(f, "Initial version."),
+                    ChangeType::Identical => {
+                        writeln!(f, "No changes since the previous version.")
+                    }
+                    ChangeType::Binary => {
+                        writeln!(f, "Binary file differs from the previous version.")
+                    }
+                    ChangeType::Modified(patch) => write!(f, "{patch}"),
+                }
+            })?;
+
+            writeln!(f)
+        })
+    }
+}
+
+impl Serialize for ChangelogEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("ChangelogEntry", 7)?;
+
+        let size = self.pathdata.get_md_infallible().size;
+        let opt_size_delta = self
+            .opt_previous_size
+            .map(|previous_size| size as i64 - previous_size as i64);
+
+        let (change_type, opt_patch) = match &self.change_type {
+            ChangeType::Initial => ("initial", None),
+            ChangeType::Identical => ("identical", None),
+            ChangeType::Binary => ("binary", None),
+            ChangeType::Modified(patch) => ("modified", Some(patch)),
+        };
+
+        state.serialize_field("path", &self.pathdata.path_buf)?;
+        state.serialize_field("metadata", &self.pathdata.metadata)?;
+        state.serialize_field("snapshot_name", &self.opt_snap_name)?;
+        state.serialize_field("is_live", &self.is_live)?;
+        state.serialize_field("size_delta", &opt_size_delta)?;
+        state.serialize_field("change", change_type)?;
+        state.serialize_field("patch", &opt_patch)?;
+        state.end()
+    }
+}
+
+impl Serialize for Changelog {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Changelog", 1)?;
+
+        let new_map: BTreeMap<String, &Vec<ChangelogEntry>> = self
+            .inner
+            .iter()
+            .map(|(key, values)| (key.path_buf.to_string_lossy().to_string(), values))
+            .collect();
+
+        state.serialize_field("changelog", &new_map)?;
+        state.end()
+    }
+}
@@ -65,9 +65,11 @@ impl SpawnDeletedThread {
         // obtain all unique deleted, unordered, unsorted, will need to fix
         let vec_deleted = DeletedFilesBundle::new(requested_dir);
 
+        let (vec_deleted, vec_renamed) = vec_deleted.into_inner_and_renamed();
+
         // combined entries will be sent or printed, but we need the vec_dirs to recurse
         let (vec_dirs, vec_files): (Vec<BasicDirEntryInfo>, Vec<BasicDirEntryInfo>) =
-            vec_deleted.into_inner().into_iter().partition(|entry| {
+            vec_deleted.into_iter().partition(|entry| {
                 // no need to traverse symlinks in deleted search
                 SharedRecursive::is_entry_dir(entry)
             });
@@ -80,6 +82,10 @@ impl SpawnDeletedThread {
             skim_tx,
         )?;
 
+        if !vec_renamed.is_empty() {
+            SharedRecursive::display_or_transmit_renamed(vec_renamed, requested_dir, skim_tx)?;
+        }
+
         // disable behind deleted dirs with DepthOfOne,
         // otherwise recurse and find all those deleted files
         //
@@ -0,0 +1,158 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::read;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use terminal_size::{terminal_size, Width};
+
+use crate::config::generate::{DiffMode, PrintMode};
+use crate::data::paths::PathData;
+use crate::library::line_diff::{is_binary, LineDiff};
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{
+    display_human_size, display_human_size_delta, get_date, print_output_buf,
+};
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+const DEFAULT_SIDE_BY_SIDE_WIDTH: usize = 160;
+
+pub struct DiffVersions;
+
+impl DiffVersions {
+    pub fn exec(diff_mode: &DiffMode) -> HttmResult<()> {
+        let (old, new) = Self::get_pair()?;
+
+        let output_buf = Self::diff(&old.path_buf, &new.path_buf, diff_mode, Self::use_color())?;
+
+        print_output_buf(output_buf)
+    }
+
+    // only color diffs when pretty printing to a terminal, so they remain useful as patches
+    pub fn use_color() -> bool {
+        matches!(GLOBAL_CONFIG.print_mode, PrintMode::FormattedDefault)
+            && std::io::stdout().is_terminal()
+    }
+
+    // two paths are compared in the order given, but with only one path, we pair
+    // a snapshot version with its live version, or a live version with its most recent snapshot version
+    fn get_pair() -> HttmResult<(PathData, PathData)> {
+        match GLOBAL_CONFIG.paths.as_slice() {
+            [old, new] => Ok((old.clone(), new.clone())),
+            [single] => {
+                if let Some(live_path) = DeconstructedSnapPathData::from_snap_mount(single)
+                    .and_then(|deconstructed| deconstructed.get_live_path())
+                {
+                    return Ok((single.clone(), PathData::from(live_path.as_path())));
+                }
+
+                let versions_map = VersionsMap::new(&GLOBAL_CONFIG, std::slice::from_ref(single))?;
+
+                match versions_map.values().flatten().last() {
+                    Some(last_snap) => Ok((last_snap.clone(), single.clone())),
+                    None => {
+                        let msg = format!(
+                            "httm could not find any snapshot versions to diff against for the file specified: {:?}",
+                            single.path_buf
+                        );
+                        Err(HttmError::new(&msg).into())
+                    }
+                }
+            }
+            _ => Err(HttmError::new(
+                "DIFF requires either two paths, or a single path for which a snapshot or live version can be found.",
+            )
+            .into()),
+        }
+    }
+
+    pub fn diff(old: &Path, new: &Path, diff_mode: &DiffMode, color: bool) -> HttmResult<String> {
+        if old.is_dir() || new.is_dir() {
+            return Err(
+                HttmError::new("httm can only diff files, but a directory was specified.").into(),
+            );
+        }
+
+        let old_bytes = read(old)?;
+        let new_bytes = read(new)?;
+
+        let mut buffer = String::new();
+
+        if old_bytes == new_bytes {
+            buffer += &format!("Files {old:?} and {new:?} are identical\n");
+        } else if is_binary(&old_bytes) || is_binary(&new_bytes) {
+            buffer += &format!("Binary files {old:?} and {new:?} differ\n");
+        } else {
+            let old_text = String::from_utf8_lossy(&old_bytes);
+            let new_text = String::from_utf8_lossy(&new_bytes);
+            let line_diff = LineDiff::new(&old_text, &new_text);
+
+            buffer += &match diff_mode {
+                DiffMode::Unified => line_diff.unified(
+                    &Self::get_label(old),
+                    &Self::get_label(new),
+                    DEFAULT_CONTEXT_LINES,
+                    color,
+                ),
+                DiffMode::SideBySide => {
+                    let width = match terminal_size() {
+                        Some((Width(width), _)) => width as usize,
+                        None => DEFAULT_SIDE_BY_SIDE_WIDTH,
+                    };
+
+                    let header = format!("{:?}\n{:?}\n", old, new);
+                    header + &line_diff.side_by_side(width, color)
+                }
+            };
+
+            buffer += &format!(
+                "{} line/s added, {} line/s removed\n",
+                line_diff.num_inserted(),
+                line_diff.num_deleted()
+            );
+        }
+
+        buffer += &Self::get_size_summary(old_bytes.len() as u64, new_bytes.len() as u64);
+
+        Ok(buffer)
+    }
+
+    pub fn get_label(path: &Path) -> String {
+        let pathdata = PathData::from(path);
+
+        let date = get_date(
+            GLOBAL_CONFIG.requested_utc_offset,
+            &pathdata.get_md_infallible().modify_time,
+            GLOBAL_CONFIG.requested_date_format.clone(),
+        );
+
+        format!("{}\t{date}", path.to_string_lossy())
+    }
+
+    fn get_size_summary(old_size: u64, new_size: u64) -> String {
+        format!(
+            "Size: {} -> {} ({})\n",
+            display_human_size(old_size),
+            display_human_size(new_size),
+            display_human_size_delta(old_size, new_size)
+        )
+    }
+}
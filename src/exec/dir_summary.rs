@@ -0,0 +1,296 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use rayon::prelude::*;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::config::generate::PrintMode;
+use crate::data::paths::PathData;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{
+    display_human_size, get_date, get_delimiter, print_output_buf, HttmIsDir, JsonDate,
+};
+use crate::lookup::deletion_window::SnapInstant;
+use crate::lookup::dir_contents::{DirContents, EntryChanges};
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+// a directory version, compared to the unique directory version which came before it
+pub struct DirSummaryEntry {
+    pathdata: PathData,
+    opt_snap_instant: Option<SnapInstant>,
+    is_live: bool,
+    num_files: usize,
+    total_size: u64,
+    // the first version has nothing to compare against
+    opt_changes: Option<EntryChanges>,
+}
+
+pub struct DirSummary {
+    inner: BTreeMap<PathData, Vec<DirSummaryEntry>>,
+}
+
+impl DirSummary {
+    pub fn exec(versions_map: VersionsMap) -> HttmResult<()> {
+        let dir_summary = Self::new(versions_map)?;
+
+        let output_buf = if GLOBAL_CONFIG.opt_json {
+            dir_summary.to_json()
+        } else {
+            dir_summary.to_string()
+        };
+
+        print_output_buf(output_buf)
+    }
+
+    fn new(versions_map: VersionsMap) -> HttmResult<Self> {
+        let inner = versions_map
+            .inner
+            .into_iter()
+            .map(|(live_version, snap_versions)| {
+                if live_version.metadata.is_some() && !live_version.httm_is_dir() {
+                    let msg = format!(
+                        "Path specified is not a directory, and therefore not suitable for a directory summary: {:?}",
+                        live_version.path_buf
+                    );
+                    return Err(HttmError::new(&msg).into());
+                }
+
+                // versions are compared in the order their snapshots were taken, as a dir's own
+                // modify time says little about when its contents changed
+                let mut versions = snap_versions;
+                versions.sort_by_cached_key(|pathdata| {
+                    SnapInstant::from_version(pathdata).map(|snap_instant| snap_instant.time)
+                });
+
+                // the summary always ends with the live version, if one exists
+                if live_version.metadata.is_some() {
+                    versions.push(live_version.clone());
+                }
+
+                let entries = Self::get_entries(&live_version, &versions);
+
+                Ok((live_version, entries))
+            })
+            .collect::<HttmResult<BTreeMap<PathData, Vec<DirSummaryEntry>>>>()?;
+
+        Ok(Self { inner })
+    }
+
+    fn get_entries(live_version: &PathData, versions: &[PathData]) -> Vec<DirSummaryEntry> {
+        // walking each version is the expensive part, so do so in parallel,
+        // a par_iter() over a slice preserves the order of versions
+        let all_contents: Vec<DirContents> = versions
+            .par_iter()
+            .map(|pathdata| DirContents::new(&pathdata.path_buf))
+            .collect();
+
+        let mut opt_previous: Option<&DirContents> = None;
+
+        versions
+            .iter()
+            .zip(all_contents.iter())
+            .filter_map(|(pathdata, contents)| {
+                let opt_changes = opt_previous.map(|previous| contents.compare(previous));
+
+                // only unique versions are displayed, but the live version is always displayed
+                let is_live = pathdata == live_version;
+
+                if !is_live && opt_changes.is_some_and(|changes| changes.is_unchanged()) {
+                    return None;
+                }
+
+                opt_previous = Some(contents);
+
+                let opt_snap_instant = if is_live {
+                    None
+                } else {
+                    SnapInstant::from_version(pathdata)
+                };
+
+                Some(DirSummaryEntry {
+                    pathdata: pathdata.clone(),
+                    opt_snap_instant,
+                    is_live,
+                    num_files: contents.get_num_files(),
+                    total_size: contents.get_total_size(),
+                    opt_changes,
+                })
+            })
+            .collect()
+    }
+
+    fn to_json(&self) -> String {
+        let res = match GLOBAL_CONFIG.print_mode {
+            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                serde_json::to_string(self)
+            }
+            PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+        };
+
+        match res {
+            Ok(s) => {
+                let delimiter = get_delimiter();
+                format!("{s}{delimiter}")
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                std::process::exit(1)
+            }
+        }
+    }
+}
+
+impl DirSummaryEntry {
+    // the time of the snapshot, or, for the live version, the directory's modify time
+    fn get_time(&self) -> SystemTime {
+        match &self.opt_snap_instant {
+            Some(snap_instant) => snap_instant.time,
+            None => self.pathdata.get_md_infallible().modify_time,
+        }
+    }
+}
+
+impl std::fmt::Display for DirSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = get_delimiter();
+
+        self.inner.iter().try_for_each(|(live_version, entries)| {
+            if matches!(
+                GLOBAL_CONFIG.print_mode,
+                PrintMode::RawNewline | PrintMode::RawZero
+            ) {
+                return entries.iter().try_for_each(|entry| {
+                    let secs = entry
+                        .get_time()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .map(|duration| duration.as_secs())
+                        .unwrap_or_default();
+
+                    let (added, removed, changed) = match entry.opt_changes {
+                        Some(changes) => (changes.added, changes.removed, changes.changed),
+                        None => (0, 0, 0),
+                    };
+
+                    write!(
+                        f,
+                        "{}\t{secs}\t{}\t{}\t{added}\t{removed}\t{changed}{delimiter}",
+                        entry.pathdata.path_buf.to_string_lossy(),
+                        entry.num_files,
+                        entry.total_size,
+                    )
+                });
+            }
+
+            writeln!(f, "Directory summary: {:?}", live_version.path_buf)?;
+
+            if entries.is_empty() {
+                writeln!(f, "No versions of this directory could be found.")?;
+            }
+
+            writeln!(f, "{:─<80}", "")?;
+
+            entries.iter().try_for_each(|entry| {
+                let date = get_date(
+                    GLOBAL_CONFIG.requested_utc_offset,
+                    &entry.get_time(),
+                    GLOBAL_CONFIG.requested_date_format.clone(),
+                );
+
+                let version = match &entry.opt_snap_instant {
+                    Some(snap_instant) => snap_instant.snap_name.as_str(),
+                    None if entry.is_live => "live version",
+                    None => "snapshot version",
+                };
+
+                let changes = match entry.opt_changes {
+                    Some(changes) => format!(
+                        "+{} -{} ~{}",
+                        changes.added, changes.removed, changes.changed
+                    ),
+                    None => "initial".to_owned(),
+                };
+
+                writeln!(
+                    f,
+                    "{date}  {:>8} files  {:>10}  {changes:<20}  {version}",
+                    entry.num_files,
+                    display_human_size(entry.total_size),
+                )
+            })?;
+
+            writeln!(f)
+        })
+    }
+}
+
+impl Serialize for DirSummaryEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("DirSummaryEntry", 9)?;
+
+        state.serialize_field("path", &self.pathdata.path_buf)?;
+        state.serialize_field(
+            "snapshot_name",
+            &self
+                .opt_snap_instant
+                .as_ref()
+                .map(|snap_instant| &snap_instant.snap_name),
+        )?;
+        state.serialize_field("is_live", &self.is_live)?;
+        state.serialize_field("files", &self.num_files)?;
+
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            state.serialize_field("size", &self.total_size)?;
+        } else {
+            state.serialize_field("size", &display_human_size(self.total_size))?;
+        }
+
+        state.serialize_field("time", &JsonDate::new(&self.get_time()))?;
+
+        state.serialize_field("added", &self.opt_changes.map(|changes| changes.added))?;
+        state.serialize_field("removed", &self.opt_changes.map(|changes| changes.removed))?;
+        state.serialize_field("changed", &self.opt_changes.map(|changes| changes.changed))?;
+        state.end()
+    }
+}
+
+impl Serialize for DirSummary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("DirSummary", 1)?;
+
+        let new_map: BTreeMap<String, &Vec<DirSummaryEntry>> = self
+            .inner
+            .iter()
+            .map(|(key, values)| (key.path_buf.to_string_lossy().to_string(), values))
+            .collect();
+
+        state.serialize_field("dir_summary", &new_map)?;
+        state.end()
+    }
+}
@@ -0,0 +1,232 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::ffi::OsStr;
+use std::fs::{create_dir_all, read, read_dir, read_link};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::data::paths::PathData;
+use crate::library::git_objects::{
+    to_hex, BareRepo, GitObjectType, ObjectId, RepoWriter, TarWriter, TreeEntry,
+};
+use crate::library::results::{HttmError, HttmResult};
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::lookup::versions::VersionsMap;
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, GLOBAL_CONFIG, ZFS_HIDDEN_DIRECTORY};
+
+pub struct ExportGit;
+
+impl ExportGit {
+    pub fn exec(dest: &Path) -> HttmResult<()> {
+        let pathdata = match GLOBAL_CONFIG.paths.as_slice() {
+            [single] => single,
+            _ => {
+                return Err(
+                    HttmError::new("EXPORT_GIT requires exactly one file or directory.").into(),
+                )
+            }
+        };
+
+        if dest.exists() {
+            let msg = format!("httm will not overwrite the existing path: {dest:?}");
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let versions_map = VersionsMap::new(&GLOBAL_CONFIG, std::slice::from_ref(pathdata))?;
+
+        // unique versions in time order, ending with the live version, if one exists
+        let mut versions: Vec<PathData> = versions_map.values().flatten().cloned().collect();
+        if pathdata.metadata.is_some() {
+            versions.push(pathdata.clone());
+        }
+
+        let writer = if dest.extension() == Some(OsStr::new("tar")) {
+            let prefix = dest
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "httm.git".to_owned());
+            RepoWriter::Tar(TarWriter::new(dest, &prefix)?)
+        } else {
+            create_dir_all(dest)?;
+            RepoWriter::Dir(dest.to_path_buf())
+        };
+
+        let mut repo = BareRepo::new(writer)?;
+
+        let file_name = pathdata.path_buf.file_name().unwrap_or_default();
+
+        let mut opt_head: Option<ObjectId> = None;
+        let mut opt_last_tree: Option<ObjectId> = None;
+        let mut num_commits = 0usize;
+
+        for version in versions.iter() {
+            // a directory version is the root of its tree, but a file version is
+            // a single entry within a tree, named after the live file
+            let tree = if version.path_buf.is_dir() {
+                match Self::write_dir_tree(&mut repo, &version.path_buf)? {
+                    Some(tree) => tree,
+                    None => repo.write_tree(Vec::new())?,
+                }
+            } else {
+                let entries = Self::get_entry(&mut repo, &version.path_buf, file_name)?
+                    .into_iter()
+                    .collect();
+                repo.write_tree(entries)?
+            };
+
+            // only one commit per distinct version
+            if opt_last_tree == Some(tree) {
+                continue;
+            }
+
+            let commit = repo.write_commit(
+                &tree,
+                opt_head.as_ref(),
+                &Self::get_signature(version),
+                &Self::get_message(version, version == pathdata),
+            )?;
+
+            opt_last_tree = Some(tree);
+            opt_head = Some(commit);
+            num_commits += 1;
+        }
+
+        repo.finish(opt_head.as_ref())?;
+
+        match opt_head {
+            Some(head) => println!(
+                "httm exported {num_commits} commit/s of {:?} to {dest:?}, the most recent of which is {}",
+                pathdata.path_buf,
+                to_hex(&head)
+            ),
+            None => eprintln!(
+                "WARNING: httm could not find any versions of {:?} to export.",
+                pathdata.path_buf
+            ),
+        }
+
+        Ok(())
+    }
+
+    fn get_entry(repo: &mut BareRepo, path: &Path, name: &OsStr) -> HttmResult<Option<TreeEntry>> {
+        let md = path.symlink_metadata()?;
+        let file_type = md.file_type();
+
+        let (mode, object_id) = if file_type.is_symlink() {
+            let target = read_link(path)?;
+            (
+                TreeEntry::MODE_SYMLINK,
+                repo.write_object(GitObjectType::Blob, target.as_os_str().as_bytes())?,
+            )
+        } else if file_type.is_dir() {
+            // git has no notion of an empty directory
+            match Self::write_dir_tree(repo, path)? {
+                Some(tree) => (TreeEntry::MODE_DIR, tree),
+                None => return Ok(None),
+            }
+        } else if file_type.is_file() {
+            let contents = match read(path) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    eprintln!("WARNING: httm could not read {path:?}, and will skip it: {err}");
+                    return Ok(None);
+                }
+            };
+
+            let mode = if md.permissions().mode() & 0o111 != 0 {
+                TreeEntry::MODE_EXECUTABLE
+            } else {
+                TreeEntry::MODE_FILE
+            };
+
+            (mode, repo.write_object(GitObjectType::Blob, &contents)?)
+        } else {
+            // sockets, fifos, and devices can't be stored in git
+            return Ok(None);
+        };
+
+        Ok(Some(TreeEntry {
+            mode,
+            name: name.as_bytes().to_vec(),
+            object_id,
+        }))
+    }
+
+    fn write_dir_tree(repo: &mut BareRepo, dir: &Path) -> HttmResult<Option<ObjectId>> {
+        let read_dir = match read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(err) => {
+                eprintln!("WARNING: httm could not read {dir:?}, and will skip it: {err}");
+                return Ok(None);
+            }
+        };
+
+        let mut entries: Vec<TreeEntry> = Vec::new();
+
+        for dir_entry in read_dir.flatten() {
+            let file_name = dir_entry.file_name();
+
+            // don't export snapshots of snapshots
+            if file_name == ZFS_HIDDEN_DIRECTORY || file_name == BTRFS_SNAPPER_HIDDEN_DIRECTORY {
+                continue;
+            }
+
+            if let Some(entry) = Self::get_entry(repo, &dir_entry.path(), &file_name)? {
+                entries.push(entry);
+            }
+        }
+
+        if entries.is_empty() {
+            return Ok(None);
+        }
+
+        repo.write_tree(entries).map(Some)
+    }
+
+    fn get_signature(version: &PathData) -> String {
+        let secs = version
+            .get_md_infallible()
+            .modify_time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let (hours, minutes, _seconds) = GLOBAL_CONFIG.requested_utc_offset.as_hms();
+        let sign = if hours < 0 || minutes < 0 { '-' } else { '+' };
+
+        format!(
+            "httm <httm@localhost> {secs} {sign}{:02}{:02}",
+            hours.unsigned_abs(),
+            minutes.unsigned_abs()
+        )
+    }
+
+    fn get_message(version: &PathData, is_live: bool) -> String {
+        let summary = if is_live {
+            "live version".to_owned()
+        } else {
+            DeconstructedSnapPathData::from_snap_mount(version)
+                .map(|deconstructed| deconstructed.snap_name)
+                .unwrap_or_else(|| "snapshot version".to_owned())
+        };
+
+        format!("{summary}\n\n{}", version.path_buf.to_string_lossy())
+    }
+}
@@ -0,0 +1,324 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::read_dir;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use hashbrown::HashMap;
+use rayon::prelude::*;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::config::generate::{FindOptions, PrintMode};
+use crate::data::paths::PathData;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{display_human_size, get_date, get_delimiter, print_output_buf};
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, GLOBAL_CONFIG, ZFS_HIDDEN_DIRECTORY};
+
+// a directory version is identified by its relative path, inode, and modify time, as
+// a directory's modify time changes whenever an entry is created, removed, or renamed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DirKey {
+    relative_dir: PathBuf,
+    inode: u64,
+    modify_time: SystemTime,
+}
+
+// key: a directory version, val: the names of its entries, and whether each is a directory
+//
+// snapshots of a dataset mostly share the same directories, so, with this index, each
+// unchanged directory is only read once per dataset, instead of once per snapshot
+struct DirListingIndex {
+    inner: HashMap<DirKey, Vec<(OsString, bool)>>,
+}
+
+impl DirListingIndex {
+    fn new() -> Self {
+        Self {
+            inner: HashMap::new(),
+        }
+    }
+
+    fn get_listing(&mut self, dir: &Path, key: DirKey) -> &[(OsString, bool)] {
+        self.inner.entry(key).or_insert_with(|| {
+            // no errors will be propagated here, as it is
+            // far too likely to run into a dir we don't have permissions to view
+            read_dir(dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|dir_entry| {
+                    let file_name = dir_entry.file_name();
+                    file_name != ZFS_HIDDEN_DIRECTORY && file_name != BTRFS_SNAPPER_HIDDEN_DIRECTORY
+                })
+                .map(|dir_entry| {
+                    // file_type() never traverses symlinks
+                    let is_dir = dir_entry
+                        .file_type()
+                        .map(|file_type| file_type.is_dir())
+                        .unwrap_or(false);
+
+                    (dir_entry.file_name(), is_dir)
+                })
+                .collect()
+        })
+    }
+}
+
+struct FoundEntry {
+    live_path: PathBuf,
+    // the newest snapshot version of the entry
+    version: PathData,
+    live_exists: bool,
+}
+
+pub struct FindVersions {
+    inner: BTreeMap<PathBuf, FoundEntry>,
+}
+
+impl FindVersions {
+    pub fn exec(find_options: &FindOptions) -> HttmResult<()> {
+        let datasets = Self::get_datasets(find_options.opt_dataset.as_deref())?;
+
+        let find_versions = Self::new(&datasets, find_options);
+
+        if find_versions.inner.is_empty() {
+            eprintln!("NOTICE: httm could not find any snapshot versions matching the glob given.");
+            return Ok(());
+        }
+
+        let output_buf = if GLOBAL_CONFIG.opt_json {
+            find_versions.to_json()
+        } else {
+            find_versions.to_string()
+        };
+
+        print_output_buf(output_buf)
+    }
+
+    fn get_datasets(opt_dataset: Option<&str>) -> HttmResult<Vec<(&PathBuf, &Vec<PathBuf>)>> {
+        let map_of_snaps = &GLOBAL_CONFIG.dataset_collection.map_of_snaps;
+        let map_of_datasets = &GLOBAL_CONFIG.dataset_collection.map_of_datasets;
+
+        let requested = match opt_dataset {
+            Some(requested) => requested,
+            None => return Ok(map_of_snaps.iter().collect()),
+        };
+
+        let datasets: Vec<(&PathBuf, &Vec<PathBuf>)> = map_of_snaps
+            .iter()
+            .filter(|(mount, _snap_mounts)| {
+                mount.as_path() == Path::new(requested)
+                    || map_of_datasets
+                        .get(*mount)
+                        .map(|dataset_info| dataset_info.source == requested)
+                        .unwrap_or(false)
+            })
+            .collect();
+
+        if datasets.is_empty() {
+            let msg = format!(
+                "httm could not find a dataset with snapshots whose mount or name is: {requested:?}"
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(datasets)
+    }
+
+    fn new(datasets: &[(&PathBuf, &Vec<PathBuf>)], find_options: &FindOptions) -> Self {
+        let inner: BTreeMap<PathBuf, FoundEntry> = datasets
+            .par_iter()
+            .flat_map_iter(|(mount, snap_mounts)| {
+                let mut index = DirListingIndex::new();
+                // key: relative path, val: the newest snapshot version found
+                let mut newest: HashMap<PathBuf, PathData> = HashMap::new();
+
+                snap_mounts.iter().for_each(|snap_mount| {
+                    Self::search_snap_mount(snap_mount, find_options, &mut index, &mut newest)
+                });
+
+                newest.into_iter().map(move |(relative_path, version)| {
+                    let live_path = mount.join(relative_path);
+                    let live_exists = live_path.symlink_metadata().is_ok();
+
+                    FoundEntry {
+                        live_path,
+                        version,
+                        live_exists,
+                    }
+                })
+            })
+            .map(|found| (found.live_path.clone(), found))
+            .collect();
+
+        Self { inner }
+    }
+
+    fn search_snap_mount(
+        snap_mount: &Path,
+        find_options: &FindOptions,
+        index: &mut DirListingIndex,
+        newest: &mut HashMap<PathBuf, PathData>,
+    ) {
+        // don't cross into other filesystems, like a nested snapshot mount
+        let dev = match snap_mount.symlink_metadata() {
+            Ok(md) => md.dev(),
+            Err(_) => return,
+        };
+
+        let mut queue = vec![PathBuf::new()];
+
+        while let Some(relative_dir) = queue.pop() {
+            let dir = snap_mount.join(&relative_dir);
+
+            let md = match dir.symlink_metadata() {
+                Ok(md) if md.dev() == dev => md,
+                _ => continue,
+            };
+
+            let key = DirKey {
+                relative_dir: relative_dir.clone(),
+                inode: md.ino(),
+                modify_time: md.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            };
+
+            index
+                .get_listing(&dir, key)
+                .iter()
+                .for_each(|(file_name, is_dir)| {
+                    let relative_path = relative_dir.join(file_name);
+
+                    let is_match = if find_options.match_relative_path {
+                        find_options
+                            .regex
+                            .is_match(&relative_path.to_string_lossy())
+                    } else {
+                        find_options.regex.is_match(&file_name.to_string_lossy())
+                    };
+
+                    if is_match {
+                        let version = PathData::from(snap_mount.join(&relative_path));
+
+                        match newest.get(&relative_path) {
+                            Some(previous)
+                                if previous.get_md_infallible().modify_time
+                                    >= version.get_md_infallible().modify_time => {}
+                            _ => {
+                                newest.insert(relative_path.clone(), version);
+                            }
+                        }
+                    }
+
+                    if *is_dir {
+                        queue.push(relative_path);
+                    }
+                });
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let res = match GLOBAL_CONFIG.print_mode {
+            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                serde_json::to_string(self)
+            }
+            PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+        };
+
+        match res {
+            Ok(s) => {
+                let delimiter = get_delimiter();
+                format!("{s}{delimiter}")
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                std::process::exit(1)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for FindVersions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = get_delimiter();
+
+        // in raw modes, only the paths of the newest snapshot versions are printed
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            return self.inner.values().try_for_each(|found| {
+                write!(f, "{}{delimiter}", found.version.path_buf.to_string_lossy())
+            });
+        }
+
+        self.inner.values().try_for_each(|found| {
+            let md = found.version.get_md_infallible();
+
+            let date = get_date(
+                GLOBAL_CONFIG.requested_utc_offset,
+                &md.modify_time,
+                GLOBAL_CONFIG.requested_date_format.clone(),
+            );
+
+            let status = if found.live_exists {
+                "exists live"
+            } else {
+                "deleted"
+            };
+
+            writeln!(f, "{:?} ({status})", found.live_path)?;
+            writeln!(
+                f,
+                "  {date}  {:>12}  {:?}",
+                display_human_size(md.size),
+                found.version.path_buf
+            )
+        })
+    }
+}
+
+impl Serialize for FoundEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("FoundEntry", 3)?;
+
+        state.serialize_field("path", &self.live_path)?;
+        state.serialize_field("live_exists", &self.live_exists)?;
+        state.serialize_field("version", &self.version)?;
+        state.end()
+    }
+}
+
+impl Serialize for FindVersions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("FindVersions", 1)?;
+
+        let found: Vec<&FoundEntry> = self.inner.values().collect();
+
+        state.serialize_field("find", &found)?;
+        state.end()
+    }
+}
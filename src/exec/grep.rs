@@ -0,0 +1,324 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::collections::BTreeMap;
+use std::fs::read;
+
+use rayon::prelude::*;
+use regex::Regex;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::config::generate::{BulkExclusion, PrintMode};
+use crate::data::paths::PathData;
+use crate::exec::recursive::SharedRecursive;
+use crate::library::line_diff::is_binary;
+use crate::library::results::HttmResult;
+use crate::library::utility::{get_date, get_delimiter, print_output_buf, HttmIsDir};
+use crate::lookup::deleted::DeletedFilesBundle;
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+struct LineMatch {
+    line_number: usize,
+    line: String,
+}
+
+// a version of a file which contains at least one matching line
+struct VersionMatch {
+    pathdata: PathData,
+    opt_snap_name: Option<String>,
+    is_live: bool,
+    line_matches: Vec<LineMatch>,
+}
+
+pub struct GrepVersions {
+    inner: BTreeMap<PathData, Vec<VersionMatch>>,
+}
+
+impl GrepVersions {
+    pub fn exec(regex: &Regex) -> HttmResult<()> {
+        let file_paths = Self::get_file_paths(&GLOBAL_CONFIG.paths);
+        let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &file_paths)?;
+
+        let grep_versions = Self::new(versions_map, regex);
+
+        if grep_versions.inner.is_empty() {
+            eprintln!("NOTICE: httm could not find any versions of the files specified which contain the pattern given.");
+            return Ok(());
+        }
+
+        let output_buf = if GLOBAL_CONFIG.opt_json {
+            grep_versions.to_json()
+        } else {
+            grep_versions.to_string()
+        };
+
+        print_output_buf(output_buf)
+    }
+
+    // input files are searched as given, input dirs for every file beneath them,
+    // including files deleted from those dirs, which are searched by their snapshot versions
+    fn get_file_paths(paths: &[PathData]) -> Vec<PathData> {
+        let include_deleted = !matches!(
+            GLOBAL_CONFIG.opt_bulk_exclusion,
+            Some(BulkExclusion::NoSnap)
+        );
+
+        let mut file_paths: Vec<PathData> = Vec::new();
+
+        paths.iter().for_each(|pathdata| {
+            if !pathdata.httm_is_dir() {
+                file_paths.push(pathdata.clone());
+                return;
+            }
+
+            let mut queue = vec![pathdata.path_buf.clone()];
+
+            while let Some(dir) = queue.pop() {
+                // a pseudo live path, in the live dir, resolves to the deleted file's snapshot
+                // versions.  like RECOVER_DELETED, the contents of deleted dirs are not searched
+                if include_deleted {
+                    let (vec_deleted, _renamed) =
+                        DeletedFilesBundle::new(&dir).into_inner_and_renamed();
+
+                    file_paths.extend(
+                        vec_deleted
+                            .iter()
+                            .filter(|basic_info| !basic_info.httm_is_dir())
+                            .map(|basic_info| PathData::from(dir.join(basic_info.get_filename()))),
+                    );
+                }
+
+                // no errors will be propagated here, as it is
+                // far too likely to run into a dir we don't have permissions to view
+                if let Ok((vec_dirs, vec_files)) = SharedRecursive::get_entries_partitioned(&dir) {
+                    file_paths.extend(vec_files.iter().map(PathData::from));
+                    queue.extend(vec_dirs.into_iter().map(|basic_info| basic_info.path));
+                }
+            }
+        });
+
+        file_paths
+    }
+
+    fn new(versions_map: VersionsMap, regex: &Regex) -> Self {
+        let include_live = !matches!(
+            GLOBAL_CONFIG.opt_bulk_exclusion,
+            Some(BulkExclusion::NoLive)
+        );
+        let include_snaps = !matches!(
+            GLOBAL_CONFIG.opt_bulk_exclusion,
+            Some(BulkExclusion::NoSnap)
+        );
+
+        let candidates: Vec<(&PathData, &PathData, bool)> = versions_map
+            .iter()
+            .flat_map(|(live_version, snap_versions)| {
+                let snaps = snap_versions
+                    .iter()
+                    .filter(move |_| include_snaps)
+                    .map(move |snap_version| (live_version, snap_version, false));
+
+                let live = Some((live_version, live_version, true))
+                    .filter(|_| include_live && live_version.metadata.is_some());
+
+                snaps.chain(live)
+            })
+            .collect();
+
+        // a par_iter() over a Vec preserves the order of versions
+        let matches: Vec<(&PathData, VersionMatch)> = candidates
+            .into_par_iter()
+            .filter_map(|(live_version, version, is_live)| {
+                Self::search_version(version, is_live, regex)
+                    .map(|version_match| (live_version, version_match))
+            })
+            .collect();
+
+        let mut inner: BTreeMap<PathData, Vec<VersionMatch>> = BTreeMap::new();
+
+        matches
+            .into_iter()
+            .for_each(|(live_version, version_match)| {
+                inner
+                    .entry(live_version.clone())
+                    .or_default()
+                    .push(version_match)
+            });
+
+        Self { inner }
+    }
+
+    fn search_version(version: &PathData, is_live: bool, regex: &Regex) -> Option<VersionMatch> {
+        let bytes = read(&version.path_buf).ok()?;
+
+        if is_binary(&bytes) {
+            return None;
+        }
+
+        let text = String::from_utf8_lossy(&bytes);
+
+        let line_matches: Vec<LineMatch> = text
+            .lines()
+            .enumerate()
+            .filter(|(_idx, line)| regex.is_match(line))
+            .map(|(idx, line)| LineMatch {
+                line_number: idx + 1,
+                line: line.to_owned(),
+            })
+            .collect();
+
+        if line_matches.is_empty() {
+            return None;
+        }
+
+        let opt_snap_name = if is_live {
+            None
+        } else {
+            DeconstructedSnapPathData::from_snap_mount(version)
+                .map(|deconstructed| deconstructed.snap_name)
+        };
+
+        Some(VersionMatch {
+            pathdata: version.clone(),
+            opt_snap_name,
+            is_live,
+            line_matches,
+        })
+    }
+
+    fn to_json(&self) -> String {
+        let res = match GLOBAL_CONFIG.print_mode {
+            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                serde_json::to_string(self)
+            }
+            PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+        };
+
+        match res {
+            Ok(s) => {
+                let delimiter = get_delimiter();
+                format!("{s}{delimiter}")
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                std::process::exit(1)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for GrepVersions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = get_delimiter();
+
+        // in raw modes, like "grep -l", only the paths of matching versions are printed
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            return self.inner.values().flatten().try_for_each(|version_match| {
+                write!(
+                    f,
+                    "{}{delimiter}",
+                    version_match.pathdata.path_buf.to_string_lossy()
+                )
+            });
+        }
+
+        self.inner
+            .iter()
+            .try_for_each(|(live_version, version_matches)| {
+                writeln!(f, "{:?}", live_version.path_buf)?;
+
+                version_matches.iter().try_for_each(|version_match| {
+                    let date = get_date(
+                        GLOBAL_CONFIG.requested_utc_offset,
+                        &version_match.pathdata.get_md_infallible().modify_time,
+                        GLOBAL_CONFIG.requested_date_format.clone(),
+                    );
+
+                    let version = match &version_match.opt_snap_name {
+                        Some(snap_name) => snap_name.as_str(),
+                        None if version_match.is_live => "live version",
+                        None => "snapshot version",
+                    };
+
+                    writeln!(f, "{:─<80}", "")?;
+                    writeln!(f, "{date}  {version}")?;
+                    writeln!(f, "{:?}", version_match.pathdata.path_buf)?;
+
+                    version_match
+                        .line_matches
+                        .iter()
+                        .try_for_each(|line_match| {
+                            writeln!(f, "{:>8}: {}", line_match.line_number, line_match.line)
+                        })
+                })?;
+
+                writeln!(f)
+            })
+    }
+}
+
+impl Serialize for LineMatch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("LineMatch", 2)?;
+
+        state.serialize_field("line_number", &self.line_number)?;
+        state.serialize_field("line", &self.line)?;
+        state.end()
+    }
+}
+
+impl Serialize for VersionMatch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("VersionMatch", 5)?;
+
+        state.serialize_field("path", &self.pathdata.path_buf)?;
+        state.serialize_field("metadata", &self.pathdata.metadata)?;
+        state.serialize_field("snapshot_name", &self.opt_snap_name)?;
+        state.serialize_field("is_live", &self.is_live)?;
+        state.serialize_field("matches", &self.line_matches)?;
+        state.end()
+    }
+}
+
+impl Serialize for GrepVersions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("GrepVersions", 1)?;
+
+        let new_map: BTreeMap<String, &Vec<VersionMatch>> = self
+            .inner
+            .iter()
+            .map(|(key, values)| (key.path_buf.to_string_lossy().to_string(), values))
+            .collect();
+
+        state.serialize_field("grep", &new_map)?;
+        state.end()
+    }
+}
@@ -0,0 +1,152 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command as ExecProcess;
+
+use crate::config::generate::{HoldAction, ListSnapsFilters, PrintMode};
+use crate::exec::snapshot::TakeSnapshot;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{get_delimiter, print_output_buf};
+use crate::lookup::snap_names::SnapNameMap;
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+pub struct HoldSnapshots;
+
+impl HoldSnapshots {
+    pub fn exec(
+        versions_map: VersionsMap,
+        opt_filters: &Option<ListSnapsFilters>,
+        hold_action: &HoldAction,
+    ) -> HttmResult<()> {
+        let tag = match hold_action {
+            HoldAction::Hold(tag) | HoldAction::Release(tag) => tag,
+        };
+
+        if tag.trim().is_empty() {
+            return Err(HttmError::new(
+                "httm requires a non-empty tag to hold or release a snapshot.",
+            )
+            .into());
+        }
+
+        let zfs_command = TakeSnapshot::get_zfs_command()?;
+
+        let snap_name_map: SnapNameMap = SnapNameMap::exec(&versions_map, opt_filters);
+
+        // a snapshot may contain more than one of the input files, and only ZFS snapshot names contain a '@'
+        let snap_names: BTreeSet<&str> = snap_name_map
+            .values()
+            .flatten()
+            .filter(|snap_name| snap_name.contains('@'))
+            .map(String::as_str)
+            .collect();
+
+        if snap_names.is_empty() {
+            return Err(HttmError::new(
+                "httm could not find any ZFS snapshots which contain the input file/s.",
+            )
+            .into());
+        }
+
+        // each snapshot is held or released separately, as "zfs hold" with many snapshots
+        // fails entirely, if the tag already exists upon any one of them
+        let changed: Vec<&str> = snap_names
+            .into_iter()
+            .filter(
+                |snap_name| match Self::hold_or_release(&zfs_command, hold_action, snap_name) {
+                    Ok(_) => true,
+                    Err(error) => {
+                        eprintln!("WARNING: {error}");
+                        false
+                    }
+                },
+            )
+            .collect();
+
+        if changed.is_empty() {
+            let msg = match hold_action {
+                HoldAction::Hold(_) => "httm was unable to place a hold on any snapshot.",
+                HoldAction::Release(_) => "httm was unable to release a hold on any snapshot.",
+            };
+
+            return Err(HttmError::new(msg).into());
+        }
+
+        print_output_buf(Self::format_output(hold_action, &changed))
+    }
+
+    fn hold_or_release(
+        zfs_command: &Path,
+        hold_action: &HoldAction,
+        snap_name: &str,
+    ) -> HttmResult<()> {
+        let process_args = match hold_action {
+            HoldAction::Hold(tag) => vec!["hold", tag.as_str(), snap_name],
+            HoldAction::Release(tag) => vec!["release", tag.as_str(), snap_name],
+        };
+
+        let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if stderr_string.is_empty() {
+            return Ok(());
+        }
+
+        let msg = match hold_action {
+            // a snapshot which already has a hold with this tag is pinned just the same
+            HoldAction::Hold(_) if stderr_string.contains("tag already exists") => return Ok(()),
+            _ if stderr_string.contains("permission denied") => {
+                "httm must have root privileges to hold or release a snapshot".to_owned()
+            }
+            _ => "httm was unable to hold or release a snapshot. The 'zfs' command issued the following error: ".to_owned() + stderr_string,
+        };
+
+        Err(HttmError::new(&msg).into())
+    }
+
+    fn format_output(hold_action: &HoldAction, snap_names: &[&str]) -> String {
+        let delimiter = get_delimiter();
+
+        match GLOBAL_CONFIG.print_mode {
+            PrintMode::RawNewline | PrintMode::RawZero => snap_names
+                .iter()
+                .map(|snap_name| format!("{snap_name}{delimiter}"))
+                .collect(),
+            PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
+                let heading = match hold_action {
+                    HoldAction::Hold(tag) => format!(
+                        "httm placed a hold with tag \"{tag}\" on the following snapshot/s, which are now pinned:\n\n"
+                    ),
+                    HoldAction::Release(tag) => format!(
+                        "httm released the hold with tag \"{tag}\" from the following snapshot/s:\n\n"
+                    ),
+                };
+
+                let snap_names_string: String = snap_names
+                    .iter()
+                    .map(|snap_name| format!("{snap_name}\n"))
+                    .collect();
+
+                heading + &snap_names_string
+            }
+        }
+    }
+}
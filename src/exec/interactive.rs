@@ -23,12 +23,16 @@ use skim::prelude::*;
 use crate::config::generate::{ExecMode, InteractiveMode, PrintMode, RestoreMode};
 use crate::data::paths::{PathData, PathMetadata};
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
-use crate::exec::preview::PreviewSelection;
+use crate::exec::preview::{DiffPreviewCandidate, PreviewSelection};
 use crate::exec::recursive::InteractiveRecursive;
+use crate::library::audit::audit;
+use crate::library::hooks::{run_hooks, HookOperation, HookType};
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::{
-    copy_recursive, get_date, get_delimiter, print_output_buf, DateFormat, Never,
+    copy_recursive, get_date, get_delimiter, get_size_recursive, print_output_buf, DateFormat,
+    Never,
 };
+use crate::lookup::snap_names::DeconstructedSnapPathData;
 use crate::lookup::versions::VersionsMap;
 use crate::GLOBAL_CONFIG;
 
@@ -312,7 +316,43 @@ impl InteractiveRestore {
 
             match user_consent.as_ref() {
                 "YES" | "Y" => {
-                    copy_recursive(&snap_pathdata.path_buf, &new_file_path_buf, should_preserve)?;
+                    let hook_paths = [snap_pathdata.path_buf.clone(), new_file_path_buf.clone()];
+                    let hook_snap_names: Vec<String> =
+                        DeconstructedSnapPathData::from_snap_mount(&snap_pathdata)
+                            .map(|deconstructed| deconstructed.snap_name)
+                            .into_iter()
+                            .collect();
+
+                    run_hooks(
+                        HookType::Pre,
+                        HookOperation::Restore,
+                        &hook_paths,
+                        &hook_snap_names,
+                    )?;
+
+                    let res = copy_recursive(
+                        &snap_pathdata.path_buf,
+                        &new_file_path_buf,
+                        should_preserve,
+                    );
+                    let opt_bytes_written = res
+                        .is_ok()
+                        .then(|| get_size_recursive(&snap_pathdata.path_buf));
+                    audit(
+                        HookOperation::Restore,
+                        &hook_paths,
+                        &hook_snap_names,
+                        opt_bytes_written,
+                        &res,
+                    );
+                    res?;
+
+                    run_hooks(
+                        HookType::Post,
+                        HookOperation::Restore,
+                        &hook_paths,
+                        &hook_snap_names,
+                    )?;
 
                     let result_buffer = format!(
                         "httm copied a file from a snapshot:\n\n\
@@ -445,10 +485,23 @@ pub fn select_restore_view(
         .build()
         .expect("Could not initialized skim options for select_restore_view");
 
-    let item_reader_opts = SkimItemReaderOption::default().ansi(true);
-    let item_reader = SkimItemReader::new(item_reader_opts);
+    let items = if preview_selection.is_native_diff {
+        let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) = unbounded();
 
-    let items = item_reader.of_bufread(Box::new(Cursor::new(preview_buffer.trim().to_owned())));
+        preview_buffer.trim().lines().for_each(|line| {
+            let _ = tx_item.send(Arc::new(DiffPreviewCandidate::new(
+                line,
+                &preview_selection.opt_live_version,
+            )));
+        });
+
+        rx_item
+    } else {
+        let item_reader_opts = SkimItemReaderOption::default().ansi(true);
+        let item_reader = SkimItemReader::new(item_reader_opts);
+
+        item_reader.of_bufread(Box::new(Cursor::new(preview_buffer.trim().to_owned())))
+    };
 
     // run_with() reads and shows items from the thread stream created above
     let selected_items = if let Some(output) = skim::Skim::run_with(&skim_opts, Some(items)) {
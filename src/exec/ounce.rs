@@ -0,0 +1,127 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command as ExecProcess;
+use std::thread;
+
+use nix::unistd::{access, AccessFlags};
+use which::which;
+
+use crate::config::generate::{LastSnapMode, MountDisplay, OunceOptions, SnapshotOptions};
+use crate::data::paths::PathData;
+use crate::exec::snapshot::TakeSnapshot;
+use crate::library::results::{HttmError, HttmResult};
+use crate::lookup::file_mounts::MountsForFiles;
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+// a native version of the "ounce" wrapper script: snapshot the datasets of the files given
+// as arguments to a program, when those files have changes not yet committed to a snapshot,
+// and then execute that program
+pub struct Ounce;
+
+impl Ounce {
+    pub fn exec(ounce_options: &OunceOptions) -> HttmResult<()> {
+        let (program, args) =
+            match ounce_options.command.split_first() {
+                Some((program, args)) if which(program).is_ok() => (program, args),
+                _ => return Err(HttmError::new(
+                    "OUNCE requires a valid executable name as the first argument after \"--\".",
+                )
+                .into()),
+            };
+
+        let exit_code = if ounce_options.background {
+            let suffix = ounce_options.suffix.clone();
+            let handle = thread::spawn(move || Self::snapshot_if_needed(&suffix));
+
+            let exit_code = Self::exec_program(program, args)?;
+
+            // the program now has the terminal back, so we may speak up
+            match handle.join() {
+                Ok(Ok(_)) => {}
+                Ok(Err(error)) => eprintln!("WARNING: {error}"),
+                Err(_) => eprintln!("WARNING: httm's ounce background thread panicked."),
+            }
+
+            exit_code
+        } else {
+            Self::snapshot_if_needed(&ounce_options.suffix)?;
+            Self::exec_program(program, args)?
+        };
+
+        std::process::exit(exit_code)
+    }
+
+    fn exec_program(program: &OsString, args: &[OsString]) -> HttmResult<i32> {
+        let status = ExecProcess::new(program).args(args).status()?;
+
+        // a program terminated by a signal has no exit code
+        Ok(status.code().unwrap_or(1))
+    }
+
+    // like the "ounce" script, only arguments which are existing, writable paths,
+    // and not options, are files we may need to snapshot
+    pub fn get_candidate_paths(command: &[OsString]) -> Vec<PathData> {
+        command
+            .iter()
+            .skip(1)
+            .map(Path::new)
+            .filter(|path| !path.to_string_lossy().starts_with('-'))
+            .filter_map(|path| path.canonicalize().ok())
+            .filter(|path| access(path, AccessFlags::W_OK).is_ok())
+            .map(|path| PathData::from(path.as_path()))
+            .collect()
+    }
+
+    fn snapshot_if_needed(suffix: &str) -> HttmResult<()> {
+        if GLOBAL_CONFIG.paths.is_empty() {
+            return Ok(());
+        }
+
+        // the same logic as "--last-snap=no-ditto-exclusive": a file needs a snapshot
+        // only when its last snapshot version differs from its live version, so any
+        // version filters must not hide that last version
+        let mut config = GLOBAL_CONFIG.generate_display_config(&GLOBAL_CONFIG.paths);
+        config.opt_omit_ditto = false;
+        config.opt_last_snap = Some(LastSnapMode::NoDittoExclusive);
+        config.opt_version_filters = None;
+
+        let needs_snap: Vec<PathData> = VersionsMap::new(&config, &config.paths)?
+            .iter()
+            .filter(|(_pathdata, snaps)| !snaps.is_empty())
+            .map(|(pathdata, _snaps)| pathdata.clone())
+            .collect();
+
+        if needs_snap.is_empty() {
+            return Ok(());
+        }
+
+        let zfs_command = TakeSnapshot::get_zfs_command()?;
+        let mounts_for_files = MountsForFiles::from_raw_paths(&needs_snap, &MountDisplay::Target);
+
+        TakeSnapshot::snapshot_mounts(
+            &zfs_command,
+            &mounts_for_files,
+            &SnapshotOptions::from_suffix(suffix),
+        )?;
+
+        Ok(())
+    }
+}
@@ -15,17 +15,25 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::path::PathBuf;
+use std::fs::read;
+use std::path::{Path, PathBuf};
 
+use skim::prelude::*;
 use which::which;
 
+use crate::config::generate::DiffMode;
+use crate::exec::diff_versions::DiffVersions;
 use crate::exec::interactive::ViewMode;
+use crate::library::line_diff::is_binary;
 use crate::library::results::{HttmError, HttmResult};
 use crate::GLOBAL_CONFIG;
 
 pub struct PreviewSelection {
     pub opt_preview_window: Option<String>,
     pub opt_preview_command: Option<String>,
+    // the "diff" built-in is previewed by each DiffPreviewCandidate, and not by a shell command
+    pub is_native_diff: bool,
+    pub opt_live_version: Option<String>,
 }
 
 impl PreviewSelection {
@@ -39,17 +47,31 @@ impl PreviewSelection {
                     unreachable!()
                 };
 
-                PreviewSelection {
-                    opt_preview_window: Some("up:50%".to_owned()),
-                    opt_preview_command: Some(Self::parse_preview_command(
-                        defined_command,
-                        &opt_live_version,
-                    )?),
+                if defined_command == "diff" {
+                    PreviewSelection {
+                        opt_preview_window: Some("up:50%".to_owned()),
+                        // an empty preview command asks skim to call each item's preview()
+                        opt_preview_command: Some(String::new()),
+                        is_native_diff: true,
+                        opt_live_version,
+                    }
+                } else {
+                    PreviewSelection {
+                        opt_preview_window: Some("up:50%".to_owned()),
+                        opt_preview_command: Some(Self::parse_preview_command(
+                            defined_command,
+                            &opt_live_version,
+                        )?),
+                        is_native_diff: false,
+                        opt_live_version,
+                    }
                 }
             }
             _ => PreviewSelection {
                 opt_preview_window: Some(String::new()),
                 opt_preview_command: None,
+                is_native_diff: false,
+                opt_live_version: None,
             },
         };
 
@@ -133,3 +155,58 @@ impl PreviewSelection {
         Ok(res)
     }
 }
+
+// a line of the select view, which, unlike a line read by SkimItemReader,
+// can preview a diff of its snapshot version against the live version
+pub struct DiffPreviewCandidate {
+    line: AnsiString,
+    opt_live_version: Option<PathBuf>,
+}
+
+impl DiffPreviewCandidate {
+    pub fn new(line: &str, opt_live_version: &Option<String>) -> Self {
+        DiffPreviewCandidate {
+            line: AnsiString::parse(line),
+            opt_live_version: opt_live_version.as_ref().map(PathBuf::from),
+        }
+    }
+
+    fn preview_view(&self) -> HttmResult<String> {
+        // like the shell preview command, the path is the first field delimited by quotes
+        let snap_file = match self.line.stripped().split('"').nth(1) {
+            Some(path) if Path::new(path).exists() => PathBuf::from(path),
+            _ => return Ok(String::new()),
+        };
+
+        match &self.opt_live_version {
+            Some(live_version) if live_version.exists() && live_version != &snap_file => {
+                DiffVersions::diff(&snap_file, live_version, &DiffMode::Unified, true)
+            }
+            _ if snap_file.is_dir() => Ok(String::new()),
+            _ => {
+                let bytes = read(&snap_file)?;
+
+                if is_binary(&bytes) {
+                    Ok(format!("Binary file {snap_file:?}"))
+                } else {
+                    Ok(String::from_utf8_lossy(&bytes).into_owned())
+                }
+            }
+        }
+    }
+}
+
+impl SkimItem for DiffPreviewCandidate {
+    fn text(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.line.stripped())
+    }
+    fn display(&self, _context: DisplayContext<'_>) -> AnsiString {
+        self.line.clone()
+    }
+    fn preview(&self, _: PreviewContext<'_>) -> skim::ItemPreview {
+        let preview_output = self
+            .preview_view()
+            .unwrap_or_else(|error| format!("Error: {error}"));
+        skim::ItemPreview::AnsiText(preview_output)
+    }
+}
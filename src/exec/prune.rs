@@ -0,0 +1,502 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command as ExecProcess;
+
+use time::{Date, Month, PrimitiveDateTime, Time};
+
+use crate::config::generate::{MountDisplay, PruneOptions};
+use crate::exec::purge::PurgeFiles;
+use crate::exec::snapshot::{TakeSnapshot, HTTM_CREATED_PROPERTY, HTTM_CREATED_VALUE};
+use crate::library::results::{HttmError, HttmResult};
+use crate::lookup::file_mounts::MountsForFiles;
+use crate::parse::aliases::FilesystemType;
+use crate::GLOBAL_CONFIG;
+
+const ROLL_FORWARD_SUFFIX: &str = "httmSnapRollForward";
+// "[year]-[month]-[day]-[hour]:[minute]:[second]", see DATE_FORMAT_TIMESTAMP
+const TIMESTAMP_LEN: usize = 19;
+const UTC_MARKER: &str = "_UTC";
+
+// a snapshot name httm itself would have created, like "snap_2023-01-01-12:00:00_httmSnapFileMount",
+// or, for roll forward, "snap_pre_2023-01-01-12:00:00_httmSnapRollForward"
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct HttmSnapName {
+    timestamp: PrimitiveDateTime,
+    suffix: String,
+}
+
+impl HttmSnapName {
+    fn parse(snap_name: &str) -> Option<Self> {
+        let rest = snap_name.strip_prefix("snap_")?;
+
+        let (rest, is_roll_forward) = match rest
+            .strip_prefix("pre_")
+            .or_else(|| rest.strip_prefix("post_"))
+        {
+            Some(rest) => (rest, true),
+            None => (rest, false),
+        };
+
+        let timestamp = Self::parse_timestamp(rest.get(..TIMESTAMP_LEN)?)?;
+        let rest = rest.get(TIMESTAMP_LEN..)?;
+        // timestamps taken in UTC are so marked, like "snap_2023-01-01-12:00:00_UTC_httmSnapFileMount"
+        let suffix = rest
+            .strip_prefix(UTC_MARKER)
+            .and_then(|rest| rest.strip_prefix('_'))
+            .or_else(|| rest.strip_prefix('_'))?;
+
+        // post roll forward snapshots include the name of the snapshot rolled forward to,
+        // like "snap_post_2023-01-01-12:00:00_:snap_name:_httmSnapRollForward"
+        if is_roll_forward {
+            return if suffix.ends_with(ROLL_FORWARD_SUFFIX) {
+                Some(Self {
+                    timestamp,
+                    suffix: ROLL_FORWARD_SUFFIX.to_owned(),
+                })
+            } else {
+                None
+            };
+        }
+
+        if suffix.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            timestamp,
+            suffix: suffix.to_owned(),
+        })
+    }
+
+    fn parse_timestamp(raw: &str) -> Option<PrimitiveDateTime> {
+        let bytes = raw.as_bytes();
+
+        let separators = [(4, b'-'), (7, b'-'), (10, b'-'), (13, b':'), (16, b':')];
+
+        if separators.iter().any(|(idx, sep)| bytes[*idx] != *sep) {
+            return None;
+        }
+
+        let number = |range: std::ops::Range<usize>| -> Option<u16> {
+            let field = raw.get(range)?;
+
+            if !field.bytes().all(|byte| byte.is_ascii_digit()) {
+                return None;
+            }
+
+            field.parse::<u16>().ok()
+        };
+
+        let month = Month::try_from(number(5..7)? as u8).ok()?;
+        let date =
+            Date::from_calendar_date(number(0..4)? as i32, month, number(8..10)? as u8).ok()?;
+        let time = Time::from_hms(
+            number(11..13)? as u8,
+            number(14..16)? as u8,
+            number(17..19)? as u8,
+        )
+        .ok()?;
+
+        Some(PrimitiveDateTime::new(date, time))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RetentionPeriod {
+    Hourly,
+    Daily,
+    Weekly,
+}
+
+impl RetentionPeriod {
+    // snapshots which fall into the same bucket belong to the same hour, day, or week
+    fn bucket(&self, timestamp: &PrimitiveDateTime) -> (i32, u16, u8) {
+        match self {
+            RetentionPeriod::Hourly => (timestamp.year(), timestamp.ordinal(), timestamp.hour()),
+            RetentionPeriod::Daily => (timestamp.year(), timestamp.ordinal(), 0),
+            RetentionPeriod::Weekly => {
+                let (iso_year, iso_week, _weekday) = timestamp.date().to_iso_week_date();
+                (iso_year, iso_week as u16, 0)
+            }
+        }
+    }
+}
+
+pub struct PruneSnapshots;
+
+impl PruneSnapshots {
+    pub fn exec(prune_options: &PruneOptions) -> HttmResult<()> {
+        let zfs_command = TakeSnapshot::get_zfs_command()?;
+
+        let snaps_by_dataset = Self::get_httm_snaps(&zfs_command, prune_options)?;
+
+        let snaps_to_destroy: Vec<String> = snaps_by_dataset
+            .iter()
+            .flat_map(|(dataset, snaps)| {
+                Self::get_expired(snaps, prune_options)
+                    .into_iter()
+                    .map(move |snap_name| format!("{dataset}@{snap_name}"))
+            })
+            .collect();
+
+        if snaps_to_destroy.is_empty() {
+            eprintln!("httm found no snapshots to prune under the requested retention policy.");
+            return Ok(());
+        }
+
+        let dataset_names: String = snaps_by_dataset
+            .keys()
+            .map(|dataset| format!("{dataset:?}\n"))
+            .collect();
+
+        let request_description = format!(
+            "User has requested httm prune its own snapshots of the following dataset/s, \
+            keeping {}:\n\n{dataset_names}\n",
+            Self::describe_policy(prune_options)
+        );
+
+        PurgeFiles::consent_and_purge(
+            &zfs_command,
+            &request_description,
+            &snaps_to_destroy,
+            &prune_options.consent,
+        )
+    }
+
+    // key: dataset name, val: (parsed name, snapshot name) of only those snapshots httm created
+    fn get_httm_snaps(
+        zfs_command: &Path,
+        prune_options: &PruneOptions,
+    ) -> HttmResult<BTreeMap<String, Vec<(HttmSnapName, String)>>> {
+        if GLOBAL_CONFIG
+            .dataset_collection
+            .opt_map_of_aliases
+            .is_some()
+        {
+            return Err(HttmError::new(
+                "httm does not currently support pruning snapshots of user defined mount points.",
+            )
+            .into());
+        }
+
+        let mounts_for_files: MountsForFiles = MountsForFiles::new(&MountDisplay::Target);
+
+        let dataset_mounts: BTreeSet<PathBuf> = mounts_for_files
+            .values()
+            .flatten()
+            .map(|mount| mount.path_buf.clone())
+            .collect();
+
+        let mut res = BTreeMap::new();
+
+        for mount in dataset_mounts {
+            let dataset_info = match GLOBAL_CONFIG
+                .dataset_collection
+                .map_of_datasets
+                .inner
+                .get(&mount)
+            {
+                Some(dataset_info) => dataset_info,
+                None => {
+                    return Err(
+                        HttmError::new("httm was unable to parse dataset from mount!").into(),
+                    )
+                }
+            };
+
+            if !matches!(dataset_info.fs_type, FilesystemType::Zfs) {
+                eprintln!(
+                    "WARNING: httm does not currently support pruning snapshots of non-ZFS filesystems.  Skipping: {mount:?}"
+                );
+                continue;
+            }
+
+            // without a suffix, a snapshot name alone is too weak a sign httm created a snapshot,
+            // so we also require httm's user property.  with a suffix, we fall back to the name
+            // alone, so snapshots taken before httm set its property may still be pruned
+            let opt_tagged = match &prune_options.opt_suffix {
+                Some(_suffix) => None,
+                None => Some(Self::get_tagged_snaps(zfs_command, &dataset_info.source)?),
+            };
+
+            let mut snaps: Vec<(HttmSnapName, String)> = GLOBAL_CONFIG
+                .dataset_collection
+                .map_of_snaps
+                .get(&mount)
+                .into_iter()
+                .flatten()
+                .filter_map(|snap_mount| snap_mount.file_name())
+                .map(|snap_name| snap_name.to_string_lossy().to_string())
+                .filter_map(|snap_name| {
+                    HttmSnapName::parse(&snap_name).map(|parsed| (parsed, snap_name))
+                })
+                .filter(
+                    |(parsed, snap_name)| match (&prune_options.opt_suffix, &opt_tagged) {
+                        (Some(suffix), _) => &parsed.suffix == suffix,
+                        (None, Some(tagged)) => tagged.contains(snap_name),
+                        (None, None) => false,
+                    },
+                )
+                .collect();
+
+            // newest first
+            snaps.sort_unstable_by(|a, b| b.cmp(a));
+
+            res.insert(dataset_info.source.clone(), snaps);
+        }
+
+        Ok(res)
+    }
+
+    // the names of the snapshots of a dataset which bear httm's user property
+    fn get_tagged_snaps(zfs_command: &Path, dataset: &str) -> HttmResult<BTreeSet<String>> {
+        let property_column = format!("name,{HTTM_CREATED_PROPERTY}");
+
+        let process_output = ExecProcess::new(zfs_command)
+            .args([
+                "list",
+                "-H",
+                "-t",
+                "snapshot",
+                "-d",
+                "1",
+                "-o",
+                &property_column,
+                dataset,
+            ])
+            .output()?;
+
+        if !process_output.status.success() {
+            let stderr_string = String::from_utf8_lossy(&process_output.stderr);
+            let msg = format!(
+                "httm was unable to list the snapshots of {dataset:?}. The 'zfs' command issued the following error: {}",
+                stderr_string.trim()
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let tagged = String::from_utf8_lossy(&process_output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(_name, value)| value.trim() == HTTM_CREATED_VALUE)
+            .filter_map(|(name, _value)| name.split_once('@'))
+            .map(|(_dataset, snap_name)| snap_name.to_owned())
+            .collect();
+
+        Ok(tagged)
+    }
+
+    // grandfather-father-son: for each period, keep the newest snapshot of each of the most recent
+    // N periods which have a snapshot, then destroy any snapshot no period chose to keep
+    fn get_expired<'a>(
+        snaps: &'a [(HttmSnapName, String)],
+        prune_options: &PruneOptions,
+    ) -> Vec<&'a str> {
+        let policies = [
+            (RetentionPeriod::Hourly, prune_options.keep_hourly),
+            (RetentionPeriod::Daily, prune_options.keep_daily),
+            (RetentionPeriod::Weekly, prune_options.keep_weekly),
+        ];
+
+        let mut keep: BTreeSet<usize> = BTreeSet::new();
+
+        policies
+            .iter()
+            .filter(|(_period, num_to_keep)| *num_to_keep > 0)
+            .for_each(|(period, num_to_keep)| {
+                let mut opt_last_bucket = None;
+                let mut num_kept = 0usize;
+
+                for (idx, (parsed, _snap_name)) in snaps.iter().enumerate() {
+                    if num_kept >= *num_to_keep {
+                        break;
+                    }
+
+                    let bucket = period.bucket(&parsed.timestamp);
+
+                    if opt_last_bucket != Some(bucket) {
+                        keep.insert(idx);
+                        num_kept += 1;
+                        opt_last_bucket = Some(bucket);
+                    }
+                }
+            });
+
+        snaps
+            .iter()
+            .enumerate()
+            .filter(|(idx, _snap)| !keep.contains(idx))
+            .map(|(_idx, (_parsed, snap_name))| snap_name.as_str())
+            .collect()
+    }
+
+    fn describe_policy(prune_options: &PruneOptions) -> String {
+        let mut policy = Vec::new();
+
+        if prune_options.keep_hourly > 0 {
+            policy.push(format!("{} hourly", prune_options.keep_hourly));
+        }
+
+        if prune_options.keep_daily > 0 {
+            policy.push(format!("{} daily", prune_options.keep_daily));
+        }
+
+        if prune_options.keep_weekly > 0 {
+            policy.push(format!("{} weekly", prune_options.keep_weekly));
+        }
+
+        let suffix = match &prune_options.opt_suffix {
+            Some(suffix) => format!(" of snapshots with suffix '{suffix}'"),
+            None => String::new(),
+        };
+
+        format!("{}{suffix}", policy.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::generate::PurgeConsent;
+
+    fn parse_timestamp(raw: &str) -> PrimitiveDateTime {
+        HttmSnapName::parse_timestamp(raw).unwrap()
+    }
+
+    fn new_snaps(names: &[&str]) -> Vec<(HttmSnapName, String)> {
+        let mut snaps: Vec<(HttmSnapName, String)> = names
+            .iter()
+            .map(|name| (HttmSnapName::parse(name).unwrap(), (*name).to_owned()))
+            .collect();
+
+        snaps.sort_unstable_by(|a, b| b.cmp(a));
+        snaps
+    }
+
+    fn new_prune_options(
+        keep_hourly: usize,
+        keep_daily: usize,
+        keep_weekly: usize,
+    ) -> PruneOptions {
+        PruneOptions {
+            keep_hourly,
+            keep_daily,
+            keep_weekly,
+            opt_suffix: None,
+            consent: PurgeConsent::DryRun,
+        }
+    }
+
+    #[test]
+    fn parse_snap_name() {
+        let parsed = HttmSnapName::parse("snap_2023-01-01-12:00:00_httmSnapFileMount").unwrap();
+
+        assert_eq!(parsed.timestamp, parse_timestamp("2023-01-01-12:00:00"));
+        assert_eq!(parsed.suffix, "httmSnapFileMount");
+    }
+
+    #[test]
+    fn parse_utc_snap_name() {
+        let parsed =
+            HttmSnapName::parse("snap_2023-01-01-12:00:00_UTC_ounceSnapFileMount").unwrap();
+
+        assert_eq!(parsed.timestamp, parse_timestamp("2023-01-01-12:00:00"));
+        assert_eq!(parsed.suffix, "ounceSnapFileMount");
+    }
+
+    #[test]
+    fn parse_roll_forward_snap_names() {
+        let pre = HttmSnapName::parse("snap_pre_2023-01-01-12:00:00_httmSnapRollForward").unwrap();
+        let post = HttmSnapName::parse(
+            "snap_post_2023-01-01-12:00:00_:pool/data@snap_1:_httmSnapRollForward",
+        )
+        .unwrap();
+
+        assert_eq!(pre.suffix, ROLL_FORWARD_SUFFIX);
+        assert_eq!(post.suffix, ROLL_FORWARD_SUFFIX);
+        assert!(HttmSnapName::parse("snap_pre_2023-01-01-12:00:00_httmSnapFileMount").is_none());
+    }
+
+    #[test]
+    fn parse_rejects_other_snap_names() {
+        [
+            "autosnap_2023-01-01_12:00:00_hourly",
+            "snap_2023-01-01-12:00:00",
+            "snap_2023-01-01-12:00:00_",
+            "snap_2023-13-01-12:00:00_httmSnapFileMount",
+            "snap_2023-01-01 12:00:00_httmSnapFileMount",
+            "snap_2023-01-01",
+        ]
+        .iter()
+        .for_each(|snap_name| assert!(HttmSnapName::parse(snap_name).is_none(), "{snap_name}"));
+    }
+
+    #[test]
+    fn expired_keeps_newest_of_each_period() {
+        let snaps = new_snaps(&[
+            "snap_2023-01-03-12:30:00_httmSnapFileMount",
+            "snap_2023-01-03-12:10:00_httmSnapFileMount",
+            "snap_2023-01-03-11:00:00_httmSnapFileMount",
+            "snap_2023-01-02-09:00:00_httmSnapFileMount",
+            "snap_2023-01-01-09:00:00_httmSnapFileMount",
+        ]);
+
+        let expired = PruneSnapshots::get_expired(&snaps, &new_prune_options(2, 2, 0));
+
+        assert_eq!(
+            expired,
+            vec![
+                "snap_2023-01-03-12:10:00_httmSnapFileMount",
+                "snap_2023-01-01-09:00:00_httmSnapFileMount",
+            ]
+        );
+    }
+
+    #[test]
+    fn expired_weeks_are_iso_weeks() {
+        // 2023-01-01 is a Sunday, and so falls in the last ISO week of 2022
+        let snaps = new_snaps(&[
+            "snap_2023-01-03-12:00:00_httmSnapFileMount",
+            "snap_2023-01-02-12:00:00_httmSnapFileMount",
+            "snap_2023-01-01-12:00:00_httmSnapFileMount",
+            "snap_2022-12-26-12:00:00_httmSnapFileMount",
+        ]);
+
+        let expired = PruneSnapshots::get_expired(&snaps, &new_prune_options(0, 0, 2));
+
+        assert_eq!(
+            expired,
+            vec![
+                "snap_2023-01-02-12:00:00_httmSnapFileMount",
+                "snap_2022-12-26-12:00:00_httmSnapFileMount",
+            ]
+        );
+    }
+
+    #[test]
+    fn expired_with_nothing_to_keep_is_everything() {
+        let snaps = new_snaps(&["snap_2023-01-01-12:00:00_httmSnapFileMount"]);
+
+        assert_eq!(
+            PruneSnapshots::get_expired(&snaps, &new_prune_options(0, 0, 0)),
+            vec!["snap_2023-01-01-12:00:00_httmSnapFileMount"]
+        );
+    }
+}
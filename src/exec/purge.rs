@@ -15,16 +15,23 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Command as ExecProcess;
 
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use which::which;
 
-use crate::config::generate::ListSnapsFilters;
+use crate::config::generate::{ListSnapsFilters, PrintMode, PurgeConsent};
 use crate::exec::interactive::{select_restore_view, ViewMode};
+use crate::library::audit::audit;
+use crate::library::hooks::{run_hooks, HookOperation, HookType};
 use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{display_human_size, get_delimiter, print_output_buf};
 use crate::lookup::snap_names::SnapNameMap;
 use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
 
 pub struct PurgeFiles;
 
@@ -32,8 +39,9 @@ impl PurgeFiles {
     pub fn exec(
         versions_map: VersionsMap,
         opt_filters: &Option<ListSnapsFilters>,
+        consent: &PurgeConsent,
     ) -> HttmResult<()> {
-        let snap_name_map: SnapNameMap = SnapNameMap::exec(versions_map, opt_filters);
+        let snap_name_map: SnapNameMap = SnapNameMap::exec(&versions_map, opt_filters);
 
         let select_mode = if let Some(filters) = opt_filters {
             filters.select_mode
@@ -42,7 +50,7 @@ impl PurgeFiles {
         };
 
         if let Ok(zfs_command) = which("zfs") {
-            Self::interactive_purge(&zfs_command, &snap_name_map, select_mode)
+            Self::purge_files(&zfs_command, &snap_name_map, select_mode, consent)
         } else {
             Err(HttmError::new(
                 "'zfs' command not found. Make sure the command 'zfs' is in your path.",
@@ -51,10 +59,11 @@ impl PurgeFiles {
         }
     }
 
-    fn interactive_purge(
+    fn purge_files(
         zfs_command: &Path,
         snap_name_map: &SnapNameMap,
         select_mode: bool,
+        consent: &PurgeConsent,
     ) -> HttmResult<()> {
         let file_names_string: String = snap_name_map
             .keys()
@@ -72,19 +81,86 @@ impl PurgeFiles {
             snap_name_map.values().flatten().cloned().collect()
         };
 
+        let request_description = format!(
+            "User has requested snapshots related to the following file/s be purged:\n\n{file_names_string}\n"
+        );
+
+        Self::consent_and_purge(zfs_command, &request_description, &snap_names, consent)
+    }
+
+    // the consent dialog is shared by any mode which destroys snapshots, and is skipped
+    // only when the user has said "yes" ahead of time, or only wants to know what would be destroyed
+    pub fn consent_and_purge(
+        zfs_command: &Path,
+        request_description: &str,
+        snap_names: &[String],
+        consent: &PurgeConsent,
+    ) -> HttmResult<()> {
+        if snap_names.is_empty() {
+            eprintln!("httm found no snapshots to purge.  No files were purged.");
+            return Ok(());
+        }
+
+        // a "zfs destroy" dry run, first, estimates the space reclaimed, but a failed
+        // estimate is no reason to refuse a purge the user has otherwise asked for, so we only warn
+        let opt_reclaimable = match Self::get_reclaim_estimate(zfs_command, snap_names) {
+            Ok(reclaimable) => Some(reclaimable),
+            Err(error) => {
+                eprintln!("WARNING: {error}");
+                None
+            }
+        };
+
+        if let PurgeConsent::DryRun = consent {
+            let dry_run = PurgeDryRun {
+                snap_names,
+                opt_reclaimable,
+            };
+
+            return print_output_buf(dry_run.to_string());
+        }
+
         let snap_names_string: String = snap_names
             .iter()
             .map(|value| format!("{value}\n"))
             .collect();
 
+        let completed_msg = match opt_reclaimable {
+            Some(reclaimable) => format!(
+                "Purge completed successfully, reclaiming an estimated {}.",
+                display_human_size(reclaimable)
+            ),
+            None => "Purge completed successfully.".to_owned(),
+        };
+
+        let result_buffer = format!(
+            "{request_description}\
+            httm destroyed the following snapshot/s:\n\n{snap_names_string}\n\
+            {completed_msg}"
+        );
+
+        let reclaim_buffer = match opt_reclaimable {
+            Some(reclaimable) => format!(
+                "Destroying these snapshot/s would reclaim an estimated {}.\n\n",
+                display_human_size(reclaimable)
+            ),
+            None => String::new(),
+        };
+
+        if let PurgeConsent::AssumeYes = consent {
+            Self::hooked_purge_snaps(zfs_command, snap_names)?;
+            eprintln!("{result_buffer}");
+            return Ok(());
+        }
+
         let preview_buffer = format!(
-            "User has requested snapshots related to the following file/s be purged:\n\n{}\n\
-            httm will destroy the following snapshot/s:\n\n{}\n\
+            "{request_description}\
+            httm will destroy the following snapshot/s:\n\n{snap_names_string}\n\
+            {reclaim_buffer}\
             Before httm destroys these snapshot/s, it would like your consent. Continue? (YES/NO)\n\
             ─────────────────────────────────────────────────────────────────────────────\n\
             YES\n\
-            NO",
-            file_names_string, snap_names_string
+            NO"
         );
 
         // loop until user consents or doesn't
@@ -95,14 +171,7 @@ impl PurgeFiles {
 
             match user_consent.as_ref() {
                 "YES" | "Y" => {
-                    Self::purge_snaps(zfs_command, snap_name_map)?;
-
-                    let result_buffer = format!(
-                        "httm purged snapshots related to the following file/s:\n\n{}\n\
-                        By destroying the following snapshot/s:\n\n{}\n\
-                        Purge completed successfully.",
-                        file_names_string, snap_names_string
-                    );
+                    Self::hooked_purge_snaps(zfs_command, snap_names)?;
 
                     break eprintln!("{result_buffer}");
                 }
@@ -112,11 +181,73 @@ impl PurgeFiles {
             }
         }
 
-        std::process::exit(0)
+        Ok(())
     }
 
-    fn purge_snaps(zfs_command: &Path, snap_name_map: &SnapNameMap) -> HttmResult<()> {
-        snap_name_map.values().flatten().try_for_each( |snapshot_name| {
+    // "zfs destroy -nvp" only accepts a comma separated list of snapshots of the same dataset,
+    // like "pool/dataset@snap_1,snap_2", so we group snapshot names by dataset first
+    pub fn get_reclaim_estimate(zfs_command: &Path, snap_names: &[String]) -> HttmResult<u64> {
+        let mut map_of_datasets: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+        snap_names.iter().try_for_each(|full_snap_name| {
+            match full_snap_name.split_once('@') {
+                Some((dataset, snap_name)) => {
+                    map_of_datasets.entry(dataset).or_default().push(snap_name);
+                    Ok(())
+                }
+                None => {
+                    let msg = format!("{full_snap_name} is not a valid snapshot name.  A valid ZFS snapshot name requires a '@' separating dataset name and snapshot name.");
+                    Err(HttmError::new(&msg))
+                }
+            }
+        })?;
+
+        map_of_datasets
+            .iter()
+            .map(|(dataset, snaps)| {
+                let snap_list = format!("{dataset}@{}", snaps.join(","));
+                let process_args = vec!["destroy", "-nvp", &snap_list];
+
+                let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
+                let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+                if !stderr_string.is_empty() {
+                    let msg = "httm was unable to estimate the space destroying these snapshots would reclaim. The 'zfs' command issued the following error: ".to_owned() + stderr_string;
+                    return Err(HttmError::new(&msg).into());
+                }
+
+                // parsable output ends with a line like "reclaim\t1234567"
+                let stdout_string = std::str::from_utf8(&process_output.stdout)?;
+
+                let reclaim = stdout_string
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("reclaim"))
+                    .filter_map(|value| value.trim().parse::<u64>().ok())
+                    .sum::<u64>();
+
+                Ok(reclaim)
+            })
+            .sum()
+    }
+
+    fn hooked_purge_snaps(zfs_command: &Path, snap_names: &[String]) -> HttmResult<()> {
+        let paths: Vec<PathBuf> = GLOBAL_CONFIG
+            .paths
+            .iter()
+            .map(|pathdata| pathdata.path_buf.clone())
+            .collect();
+
+        run_hooks(HookType::Pre, HookOperation::Purge, &paths, snap_names)?;
+
+        let res = Self::purge_snaps(zfs_command, snap_names);
+        audit(HookOperation::Purge, &paths, snap_names, None, &res);
+        res?;
+
+        run_hooks(HookType::Post, HookOperation::Purge, &paths, snap_names)
+    }
+
+    fn purge_snaps(zfs_command: &Path, snap_names: &[String]) -> HttmResult<()> {
+        snap_names.iter().try_for_each( |snapshot_name| {
             let mut process_args = vec!["destroy".to_owned()];
             process_args.push(snapshot_name.clone());
 
@@ -138,3 +269,74 @@ impl PurgeFiles {
         })
     }
 }
+
+struct PurgeDryRun<'a> {
+    snap_names: &'a [String],
+    opt_reclaimable: Option<u64>,
+}
+
+impl<'a> std::fmt::Display for PurgeDryRun<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = get_delimiter();
+
+        if GLOBAL_CONFIG.opt_json {
+            let res = match GLOBAL_CONFIG.print_mode {
+                PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                    serde_json::to_string(self)
+                }
+                PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+            };
+
+            return match res {
+                Ok(json) => write!(f, "{json}{delimiter}"),
+                Err(_) => Err(std::fmt::Error),
+            };
+        }
+
+        match GLOBAL_CONFIG.print_mode {
+            PrintMode::RawNewline | PrintMode::RawZero => self
+                .snap_names
+                .iter()
+                .try_for_each(|snap_name| write!(f, "{snap_name}{delimiter}")),
+            PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
+                writeln!(f, "httm would destroy the following snapshot/s:\n")?;
+
+                self.snap_names
+                    .iter()
+                    .try_for_each(|snap_name| writeln!(f, "{snap_name}"))?;
+
+                match self.opt_reclaimable {
+                    Some(reclaimable) => writeln!(
+                        f,
+                        "\nDestroying these snapshot/s would reclaim an estimated {}.",
+                        display_human_size(reclaimable)
+                    ),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Serialize for PurgeDryRun<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("PurgeDryRun", 2)?;
+
+        state.serialize_field("snapshots", &self.snap_names)?;
+
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            state.serialize_field("reclaimable", &self.opt_reclaimable)?;
+        } else {
+            let opt_human_reclaimable = self.opt_reclaimable.map(display_human_size);
+            state.serialize_field("reclaimable", &opt_human_reclaimable)?;
+        }
+
+        state.end()
+    }
+}
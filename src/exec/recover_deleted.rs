@@ -0,0 +1,340 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::collections::BTreeSet;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::config::generate::{PrintMode, RecoverOptions};
+use crate::data::paths::PathData;
+use crate::exec::recursive::SharedRecursive;
+use crate::library::audit::audit;
+use crate::library::hooks::{run_hooks, HookOperation, HookType};
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{
+    copy_recursive, display_human_size, get_delimiter, get_size_recursive, print_output_buf,
+    HttmIsDir,
+};
+use crate::lookup::deleted::{DeletedFilesBundle, LastInTimeSet};
+use crate::lookup::deletion_window::DeletionWindow;
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::GLOBAL_CONFIG;
+
+// a deleted file or directory, the snapshot version from which it will be
+// recovered, and where it will be recovered to
+struct Recovery {
+    snap_path: PathBuf,
+    snap_name: Option<String>,
+    dest: PathBuf,
+}
+
+pub struct RecoverDeleted;
+
+impl RecoverDeleted {
+    pub fn exec(recover_options: &RecoverOptions) -> HttmResult<()> {
+        let requested_dir = match GLOBAL_CONFIG.paths.as_slice() {
+            [pathdata] if pathdata.httm_is_dir() => pathdata.path_buf.as_path(),
+            [pathdata] => {
+                let msg = format!(
+                    "Path specified is not a directory, and therefore not suitable for recovering deleted files: {:?}",
+                    pathdata.path_buf
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+            _ => {
+                return Err(HttmError::new(
+                    "May only specify one directory from which to recover deleted files.",
+                )
+                .into())
+            }
+        };
+
+        let recoveries = Self::get_recoveries(requested_dir, recover_options);
+
+        if recoveries.is_empty() {
+            eprintln!(
+                "NOTICE: httm could not find any deleted files or directories to recover beneath {requested_dir:?}."
+            );
+            return Ok(());
+        }
+
+        let hook_paths: Vec<PathBuf> = recoveries
+            .iter()
+            .flat_map(|recovery| [recovery.snap_path.clone(), recovery.dest.clone()])
+            .collect();
+        let hook_snap_names: Vec<String> = recoveries
+            .iter()
+            .filter_map(|recovery| recovery.snap_name.clone())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+
+        run_hooks(
+            HookType::Pre,
+            HookOperation::Restore,
+            &hook_paths,
+            &hook_snap_names,
+        )?;
+
+        let (recovered, failed): (Vec<(Recovery, u64)>, Vec<Recovery>) = recoveries
+            .into_iter()
+            .map(|recovery| match Self::recover(&recovery) {
+                Ok(bytes_written) => Ok((recovery, bytes_written)),
+                Err(err) => {
+                    eprintln!("WARNING: httm could not recover {:?}: {err}", recovery.dest);
+                    Err(recovery)
+                }
+            })
+            .fold((Vec::new(), Vec::new()), |(mut ok, mut err), res| {
+                match res {
+                    Ok(item) => ok.push(item),
+                    Err(item) => err.push(item),
+                }
+                (ok, err)
+            });
+
+        run_hooks(
+            HookType::Post,
+            HookOperation::Restore,
+            &hook_paths,
+            &hook_snap_names,
+        )?;
+
+        let output_buf = RecoveryManifest {
+            recovered: &recovered,
+        }
+        .to_string();
+
+        print_output_buf(output_buf)?;
+
+        if !failed.is_empty() {
+            let msg = format!(
+                "httm could not recover {} of {} deleted files or directories.",
+                failed.len(),
+                failed.len() + recovered.len()
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    fn recover(recovery: &Recovery) -> HttmResult<u64> {
+        // never overwrite a live file, which may have taken the place of the deleted file
+        if recovery.dest.symlink_metadata().is_ok() {
+            let msg = format!("{:?} already exists", recovery.dest);
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let paths = [recovery.snap_path.clone(), recovery.dest.clone()];
+        let snap_names: Vec<String> = recovery.snap_name.iter().cloned().collect();
+
+        let res = match recovery.dest.parent() {
+            Some(parent) => create_dir_all(parent)
+                .map_err(|err| err.into())
+                .and_then(|_| copy_recursive(&recovery.snap_path, &recovery.dest, true)),
+            None => copy_recursive(&recovery.snap_path, &recovery.dest, true),
+        };
+        let opt_bytes_written = res.is_ok().then(|| get_size_recursive(&recovery.snap_path));
+
+        audit(
+            HookOperation::Restore,
+            &paths,
+            &snap_names,
+            opt_bytes_written,
+            &res,
+        );
+        res?;
+
+        Ok(opt_bytes_written.unwrap_or_default())
+    }
+
+    fn get_recoveries(requested_dir: &Path, recover_options: &RecoverOptions) -> Vec<Recovery> {
+        let mut recoveries: Vec<Recovery> = Vec::new();
+        let mut queue: Vec<PathBuf> = vec![requested_dir.to_path_buf()];
+
+        // deleted entries are found only in live dirs, as a deleted dir is
+        // recovered along with everything beneath it
+        while let Some(live_dir) = queue.pop() {
+            DeletedFilesBundle::new(&live_dir)
+                .iter()
+                .map(|basic_info| live_dir.join(basic_info.get_filename()))
+                .filter(|pseudo_live_path| {
+                    Self::is_deleted_since(pseudo_live_path, recover_options.opt_since)
+                })
+                .filter_map(|pseudo_live_path| {
+                    Self::get_recovery(&pseudo_live_path, requested_dir, recover_options)
+                })
+                .for_each(|recovery| recoveries.push(recovery));
+
+            // no errors will be propagated here, as it is
+            // far too likely to run into a dir we don't have permissions to view
+            if let Ok((vec_dirs, _)) = SharedRecursive::get_entries_partitioned(&live_dir) {
+                queue.extend(vec_dirs.into_iter().map(|basic_info| basic_info.path));
+            }
+        }
+
+        recoveries.sort_unstable_by(|a, b| a.dest.cmp(&b.dest));
+
+        recoveries
+    }
+
+    fn get_recovery(
+        pseudo_live_path: &Path,
+        requested_dir: &Path,
+        recover_options: &RecoverOptions,
+    ) -> Option<Recovery> {
+        // same policy as deleted searches: the last in time version
+        let last_in_time_set = LastInTimeSet::new(&[PathData::from(pseudo_live_path)]);
+        let snap_path = last_in_time_set.first()?.clone();
+
+        let snap_name = DeconstructedSnapPathData::from_snap_mount(&PathData::from(&snap_path))
+            .map(|deconstructed| deconstructed.snap_name);
+
+        let dest = match &recover_options.opt_dest {
+            Some(dest_dir) => dest_dir.join(pseudo_live_path.strip_prefix(requested_dir).ok()?),
+            None => pseudo_live_path.to_path_buf(),
+        };
+
+        Some(Recovery {
+            snap_path,
+            snap_name,
+            dest,
+        })
+    }
+
+    fn is_deleted_since(pseudo_live_path: &Path, opt_since: Option<SystemTime>) -> bool {
+        match opt_since {
+            // a file was certainly deleted before the first snapshot which doesn't contain it
+            Some(since) => match DeletionWindow::new(pseudo_live_path) {
+                Some(window) => window
+                    .opt_first_missing
+                    .map_or(true, |first_missing| first_missing.time >= since),
+                None => true,
+            },
+            None => true,
+        }
+    }
+}
+
+struct RecoveryManifest<'a> {
+    recovered: &'a [(Recovery, u64)],
+}
+
+impl<'a> std::fmt::Display for RecoveryManifest<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = get_delimiter();
+
+        if GLOBAL_CONFIG.opt_json {
+            let res = match GLOBAL_CONFIG.print_mode {
+                PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                    serde_json::to_string(self)
+                }
+                PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+            };
+
+            return match res {
+                Ok(json) => write!(f, "{json}{delimiter}"),
+                Err(_) => Err(std::fmt::Error),
+            };
+        }
+
+        match GLOBAL_CONFIG.print_mode {
+            PrintMode::RawNewline | PrintMode::RawZero => {
+                self.recovered.iter().try_for_each(|(recovery, _)| {
+                    write!(f, "{}{delimiter}", recovery.dest.to_string_lossy())
+                })
+            }
+            PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
+                writeln!(
+                    f,
+                    "httm recovered the following deleted files and directories:\n"
+                )?;
+
+                self.recovered.iter().try_for_each(|(recovery, _)| {
+                    writeln!(f, "\tfrom: {:?}", recovery.snap_path)?;
+                    writeln!(f, "\tto:   {:?}\n", recovery.dest)
+                })?;
+
+                let total_bytes: u64 = self.recovered.iter().map(|(_, bytes)| bytes).sum();
+
+                writeln!(
+                    f,
+                    "Recovered {} deleted files or directories, {} in total.",
+                    self.recovered.len(),
+                    display_human_size(total_bytes)
+                )
+            }
+        }
+    }
+}
+
+impl<'a> Serialize for RecoveryManifest<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct RecoveredEntry<'b> {
+            recovery: &'b Recovery,
+            bytes_written: u64,
+        }
+
+        impl<'b> Serialize for RecoveredEntry<'b> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state = serializer.serialize_struct("RecoveredEntry", 4)?;
+
+                state.serialize_field("source", &self.recovery.snap_path)?;
+                state.serialize_field("destination", &self.recovery.dest)?;
+                state.serialize_field("snapshot", &self.recovery.snap_name)?;
+
+                if matches!(
+                    GLOBAL_CONFIG.print_mode,
+                    PrintMode::RawNewline | PrintMode::RawZero
+                ) {
+                    state.serialize_field("bytes_written", &self.bytes_written)?;
+                } else {
+                    state.serialize_field(
+                        "bytes_written",
+                        &display_human_size(self.bytes_written),
+                    )?;
+                }
+
+                state.end()
+            }
+        }
+
+        let entries: Vec<RecoveredEntry> = self
+            .recovered
+            .iter()
+            .map(|(recovery, bytes_written)| RecoveredEntry {
+                recovery,
+                bytes_written: *bytes_written,
+            })
+            .collect();
+
+        let mut state = serializer.serialize_struct("RecoveryManifest", 1)?;
+
+        state.serialize_field("recovered", &entries)?;
+        state.end()
+    }
+}
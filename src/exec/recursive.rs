@@ -27,6 +27,8 @@ use crate::display_versions::wrapper::VersionsDisplayWrapper;
 use crate::exec::deleted::SpawnDeletedThread;
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::{print_output_buf, HttmIsDir, Never};
+use crate::lookup::deleted::{RenamedEntry, RenamedMap};
+use crate::lookup::deletion_window::DeletionWindowMap;
 use crate::VersionsMap;
 use crate::GLOBAL_CONFIG;
 use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, ZFS_HIDDEN_DIRECTORY};
@@ -56,6 +58,12 @@ impl NonInteractiveRecursiveWrapper {
     }
 
     fn print(entries: Vec<BasicDirEntryInfo>) -> HttmResult<()> {
+        if GLOBAL_CONFIG.opt_deleted_when {
+            let output_buf = DeletionWindowMap::new(&entries).to_string();
+
+            return print_output_buf(output_buf);
+        }
+
         let pseudo_live_set: Vec<PathData> = entries.iter().map(PathData::from).collect();
 
         let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &pseudo_live_set)?;
@@ -311,6 +319,35 @@ impl SharedRecursive {
         Ok(())
     }
 
+    pub fn display_or_transmit_renamed(
+        renamed: Vec<RenamedEntry>,
+        pseudo_live_dir: &Path,
+        skim_tx: &SkimItemSender,
+    ) -> HttmResult<()> {
+        match &GLOBAL_CONFIG.exec_mode {
+            ExecMode::Interactive(_) => renamed
+                .into_iter()
+                .try_for_each(|entry| {
+                    let basic_info = BasicDirEntryInfo {
+                        path: pseudo_live_dir.join(entry.snap_entry.get_filename()),
+                        file_type: entry.snap_entry.file_type,
+                    };
+
+                    skim_tx.try_send(Arc::new(SelectionCandidate::renamed(
+                        basic_info,
+                        entry.live_path,
+                    )))
+                })
+                .map_err(std::convert::Into::into),
+            ExecMode::NonInteractiveRecursive(_) => {
+                let output_buf = RenamedMap::new(&renamed, pseudo_live_dir).to_string();
+
+                print_output_buf(output_buf)
+            }
+            _ => unreachable!(),
+        }
+    }
+
     fn transmit(
         entries: Vec<BasicDirEntryInfo>,
         is_phantom: bool,
@@ -25,7 +25,10 @@ use std::time::SystemTime;
 use which::which;
 
 use crate::data::paths::PathData;
+use crate::exec::snapshot::{HTTM_CREATED_PROPERTY, HTTM_CREATED_VALUE};
+use crate::library::audit::audit;
 use crate::library::diff_copy::diff_copy;
+use crate::library::hooks::{run_hooks, HookOperation, HookType};
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::{compare_modify_time, get_date, DateFormat};
 use crate::library::utility::{copy_attributes, remove_recursive};
@@ -38,7 +41,7 @@ pub enum PrecautionarySnapType {
 }
 
 #[derive(Clone)]
-enum DiffType {
+pub enum DiffType {
     Removed,
     Created,
     Modified,
@@ -74,6 +77,24 @@ impl RollForward {
             return Err(HttmError::new(&msg).into());
         };
 
+        // hooks receive the mount of the dataset rolled forward, if httm knows of it
+        let hook_paths: Vec<PathBuf> = GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_datasets
+            .inner
+            .iter()
+            .filter(|(_mount, dataset_info)| dataset_info.source == dataset_name)
+            .map(|(mount, _dataset_info)| mount.clone())
+            .collect();
+        let hook_snap_names = [full_snap_name.to_owned()];
+
+        run_hooks(
+            HookType::Pre,
+            HookOperation::RollForward,
+            &hook_paths,
+            &hook_snap_names,
+        )?;
+
         let mut process_handle = Self::exec_diff(full_snap_name, &zfs_command)?;
 
         let mut stream = Self::ingest(&mut process_handle)?;
@@ -85,7 +106,17 @@ impl RollForward {
             PrecautionarySnapType::Pre,
         )?;
 
-        match Self::roll_forward(&mut stream, snap_name, dataset_name) {
+        let res = Self::roll_forward(&mut stream, snap_name, dataset_name);
+
+        audit(
+            HookOperation::RollForward,
+            &hook_paths,
+            &hook_snap_names,
+            res.as_ref().ok().copied(),
+            &res,
+        );
+
+        match res {
             Ok(_) => {
                 println!("httm roll forward completed successfully.");
             }
@@ -109,8 +140,14 @@ impl RollForward {
             dataset_name,
             snap_name,
             PrecautionarySnapType::Post,
+        )?;
+
+        run_hooks(
+            HookType::Post,
+            HookOperation::RollForward,
+            &hook_paths,
+            &hook_snap_names,
         )
-        .map(|_res| ())
     }
 
     fn exec_rollback(pre_exec_snap_name: &str, zfs_command: &Path) -> HttmResult<()> {
@@ -152,7 +189,11 @@ impl RollForward {
         snap_name: &str,
         snap_type: PrecautionarySnapType,
     ) -> HttmResult<String> {
-        let mut process_args = vec!["snapshot".to_owned()];
+        let mut process_args = vec![
+            "snapshot".to_owned(),
+            "-o".to_owned(),
+            format!("{HTTM_CREATED_PROPERTY}={HTTM_CREATED_VALUE}"),
+        ];
 
         let timestamp = get_date(
             GLOBAL_CONFIG.requested_utc_offset,
@@ -277,10 +318,13 @@ impl RollForward {
         Ok(iterator)
     }
 
-    fn roll_forward<I>(stream: I, snap_name: &str, _dataset_name: &str) -> HttmResult<()>
+    // returns the number of bytes copied from the snapshot
+    fn roll_forward<I>(stream: I, snap_name: &str, _dataset_name: &str) -> HttmResult<u64>
     where
         I: Iterator<Item = (PathData, DiffType)>,
     {
+        let mut bytes_written = 0u64;
+
         stream.filter_map(|(pathdata, diff_type)| {
                 pathdata
                     .get_proximate_dataset(&GLOBAL_CONFIG.dataset_collection.map_of_datasets)
@@ -302,6 +346,7 @@ impl RollForward {
                     DiffType::Removed => {
                         match Self::copy_direct(&snap_file.path_buf, &pathdata.path_buf, true) {
                             Ok(_) => {
+                                bytes_written += snap_file.get_md_infallible().size;
                                 compare_modify_time(&snap_file.path_buf, &pathdata.path_buf)
                             }
                             Err(err) => {
@@ -330,6 +375,7 @@ impl RollForward {
                     DiffType::Modified => {
                         match Self::copy_direct(&snap_file.path_buf, &pathdata.path_buf, true) {
                             Ok(_) => {
+                                bytes_written += snap_file.get_md_infallible().size;
                                 compare_modify_time(&snap_file.path_buf, &pathdata.path_buf)
                             }
                             Err(err) => {
@@ -353,7 +399,9 @@ impl RollForward {
                     }
                 };
                 res
-            })
+            })?;
+
+        Ok(bytes_written)
     }
 
     // why include here? because I think this only works with the correct semantics
@@ -15,45 +15,243 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::{collections::BTreeMap, path::Path, time::SystemTime};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 use std::process::Command as ExecProcess;
 use which::which;
 
-use crate::config::generate::{MountDisplay, PrintMode};
+use crate::config::generate::{MountDisplay, PrintMode, SnapshotOptions};
+use crate::library::hooks::{run_hooks, HookOperation, HookType};
 use crate::library::iter_extensions::HttmIter;
 use crate::library::results::{HttmError, HttmResult};
-use crate::library::utility::{get_date, get_delimiter, print_output_buf, DateFormat};
+use crate::library::utility::{
+    get_date, get_delimiter, parse_template, print_output_buf, strftime_to_format_description,
+    DateFormat, TemplateSegment,
+};
 use crate::lookup::file_mounts::MountsForFiles;
 use crate::parse::aliases::FilesystemType;
 use crate::GLOBAL_CONFIG;
 
+// the prefix of every snapshot name httm creates, unless the user specifies a different template
+const SNAPSHOT_PREFIX: &str = "snap";
+const DEFAULT_SNAPSHOT_TEMPLATE: &str = "{dataset}@{prefix}_{date}_{suffix}";
+// a user property set upon every snapshot httm creates, so PRUNE can tell httm's snapshots
+// apart from those which only happen to share httm's naming scheme
+pub const HTTM_CREATED_PROPERTY: &str = "httm:created";
+pub const HTTM_CREATED_VALUE: &str = "true";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SnapNameField {
+    Dataset,
+    Prefix,
+    Suffix,
+    Date(DateFormat),
+}
+
+// a user defined snapshot name, like: "{dataset}@{prefix}-{date:%Y%m%d-%H%M}-{suffix}"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapNameTemplate {
+    segments: Vec<TemplateSegment<SnapNameField>>,
+}
+
+impl Default for SnapNameTemplate {
+    fn default() -> Self {
+        Self::new(DEFAULT_SNAPSHOT_TEMPLATE).expect("default snapshot template is invalid")
+    }
+}
+
+impl SnapNameTemplate {
+    pub fn new(raw: &str) -> HttmResult<Self> {
+        let mut segments = parse_template(raw, "SNAP_TEMPLATE", Self::parse_placeholder)?;
+
+        if segments.iter().any(
+            |segment| matches!(segment, TemplateSegment::Literal(literal) if literal.contains(char::is_whitespace)),
+        ) {
+            return Err(HttmError::new(
+                "httm will only accept snapshot templates which don't contain whitespace",
+            )
+            .into());
+        }
+
+        // a template without a dataset is only the snapshot's name, so we supply the dataset
+        let has_separator = segments.iter().any(
+            |segment| matches!(segment, TemplateSegment::Literal(literal) if literal.contains('@')),
+        );
+
+        if !has_separator {
+            segments.insert(0, TemplateSegment::Literal("@".to_owned()));
+            segments.insert(0, TemplateSegment::Placeholder(SnapNameField::Dataset));
+        }
+
+        Ok(Self { segments })
+    }
+
+    fn parse_placeholder(placeholder: &str) -> HttmResult<SnapNameField> {
+        let (name, opt_spec) = match placeholder.split_once(':') {
+            Some((name, spec)) => (name.trim(), Some(spec)),
+            None => (placeholder.trim(), None),
+        };
+
+        let field = match (name, opt_spec) {
+            ("dataset", None) => SnapNameField::Dataset,
+            ("prefix", None) => SnapNameField::Prefix,
+            ("suffix", None) => SnapNameField::Suffix,
+            ("date" | "timestamp", None) => SnapNameField::Date(DateFormat::Timestamp),
+            ("date" | "timestamp", Some(strftime)) => SnapNameField::Date(DateFormat::Custom(
+                strftime_to_format_description(strftime)?,
+            )),
+            _ => {
+                let msg = format!(
+                    "SNAP_TEMPLATE contains an unknown placeholder or specifier: \"{{{placeholder}}}\""
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+        };
+
+        Ok(field)
+    }
+
+    fn expand(&self, dataset: &str, suffix: &str, now: &SystemTime) -> HttmResult<String> {
+        let snapshot_name: String = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                TemplateSegment::Literal(literal) => literal.clone(),
+                TemplateSegment::Placeholder(field) => match field {
+                    SnapNameField::Dataset => dataset.to_owned(),
+                    SnapNameField::Prefix => SNAPSHOT_PREFIX.to_owned(),
+                    SnapNameField::Suffix => suffix.to_owned(),
+                    SnapNameField::Date(date_format) => {
+                        get_date(GLOBAL_CONFIG.requested_utc_offset, now, date_format.clone())
+                    }
+                },
+            })
+            .collect();
+
+        // the template may only name a snapshot of the dataset requested
+        match snapshot_name.split_once('@') {
+            Some((template_dataset, snap_name))
+                if template_dataset == dataset
+                    && !snap_name.is_empty()
+                    && !snap_name.contains(['@', ' ', '\t', '\n']) =>
+            {
+                Ok(snapshot_name)
+            }
+            _ => {
+                let msg = format!("SNAP_TEMPLATE produced an invalid snapshot name for dataset {dataset}: \"{snapshot_name}\".  \
+                    A valid snapshot name is the dataset's name, a single '@', and then a snapshot name without whitespace.");
+                Err(HttmError::new(&msg).into())
+            }
+        }
+    }
+}
+
 pub struct TakeSnapshot;
 
 impl TakeSnapshot {
-    pub fn exec(requested_snapshot_suffix: &str) -> HttmResult<()> {
+    pub fn exec(snapshot_options: &SnapshotOptions) -> HttmResult<()> {
         let mounts_for_files: MountsForFiles = MountsForFiles::new(&MountDisplay::Target);
 
-        if let Ok(zfs_command) = which("zfs") {
-            Self::snapshot_mounts(&zfs_command, &mounts_for_files, requested_snapshot_suffix)
-        } else {
-            Err(HttmError::new(
-                "'zfs' command not found. Make sure the command 'zfs' is in your path.",
-            )
-            .into())
-        }
+        let zfs_command = Self::get_zfs_command()?;
+
+        let snapshot_names =
+            Self::snapshot_mounts(zfs_command.as_path(), &mounts_for_files, snapshot_options)?;
+
+        print_output_buf(Self::format_snapshot_names(&snapshot_names))
+    }
+
+    pub fn format_snapshot_names(snapshot_names: &[String]) -> String {
+        snapshot_names
+            .iter()
+            .map(|snap_name| {
+                if matches!(
+                    GLOBAL_CONFIG.print_mode,
+                    PrintMode::RawNewline | PrintMode::RawZero
+                ) {
+                    let delimiter = get_delimiter();
+                    format!("{}{delimiter}", &snap_name)
+                } else {
+                    format!("httm took a snapshot named: {}\n", &snap_name)
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_zfs_command() -> HttmResult<PathBuf> {
+        which("zfs").map_err(|_err| {
+            HttmError::new("'zfs' command not found. Make sure the command 'zfs' is in your path.")
+                .into()
+        })
     }
 
-    fn snapshot_mounts(
+    // returns the names of the snapshots taken, and leaves printing to the caller,
+    // as some callers, like ounce, must not write over the terminal of another program.
+    // every caller, whether snapshot, ounce or watch, runs the user's snapshot hooks here
+    pub fn snapshot_mounts(
         zfs_command: &Path,
         mounts_for_files: &MountsForFiles,
-        requested_snapshot_suffix: &str,
-    ) -> HttmResult<()> {
-        let map_snapshot_names =
-            Self::get_snapshot_names(mounts_for_files, requested_snapshot_suffix)?;
+        snapshot_options: &SnapshotOptions,
+    ) -> HttmResult<Vec<String>> {
+        let map_snapshot_names = Self::get_snapshot_names(mounts_for_files, snapshot_options)?;
+
+        let paths: Vec<PathBuf> = mounts_for_files
+            .inner
+            .keys()
+            .map(|pathdata| pathdata.path_buf.clone())
+            .collect();
+        let snapshot_names: Vec<String> = map_snapshot_names.values().flatten().cloned().collect();
+
+        run_hooks(
+            HookType::Pre,
+            HookOperation::Snapshot,
+            &paths,
+            &snapshot_names,
+        )?;
+
+        Self::take_snapshots(zfs_command, &map_snapshot_names, snapshot_options)?;
 
-        map_snapshot_names.iter().try_for_each( |(_pool_name, snapshot_names)| {
+        run_hooks(
+            HookType::Post,
+            HookOperation::Snapshot,
+            &paths,
+            &snapshot_names,
+        )?;
+
+        Ok(snapshot_names)
+    }
+
+    // key: pool name, val: the names of the snapshots to take in that pool
+    fn take_snapshots(
+        zfs_command: &Path,
+        map_snapshot_names: &BTreeMap<String, Vec<String>>,
+        snapshot_options: &SnapshotOptions,
+    ) -> HttmResult<()> {
+        map_snapshot_names.iter().try_for_each(|(_pool_name, snapshot_names)| -> HttmResult<()> {
             let mut process_args = vec!["snapshot".to_owned()];
+
+            if snapshot_options.recursive {
+                process_args.push("-r".to_owned());
+            }
+
+            snapshot_options.properties.iter().for_each(|property| {
+                process_args.push("-o".to_owned());
+                process_args.push(property.clone());
+            });
+
+            let is_tagged_by_user = snapshot_options
+                .properties
+                .iter()
+                .any(|property| property.split_once('=').map(|(name, _value)| name) == Some(HTTM_CREATED_PROPERTY));
+
+            if !is_tagged_by_user {
+                process_args.push("-o".to_owned());
+                process_args.push(format!("{HTTM_CREATED_PROPERTY}={HTTM_CREATED_VALUE}"));
+            }
+
             process_args.extend_from_slice(snapshot_names);
 
             let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
@@ -67,42 +265,25 @@ impl TakeSnapshot {
                     "httm was unable to take snapshots. The 'zfs' command issued the following error: ".to_owned() + stderr_string
                 };
 
-                Err(HttmError::new(&msg).into())
-            } else {
-                let output_buf = snapshot_names
-                    .iter()
-                    .map(|snap_name| {
-                        if matches!(GLOBAL_CONFIG.print_mode, PrintMode::RawNewline | PrintMode::RawZero)  {
-                            let delimiter = get_delimiter();
-                            format!("{}{delimiter}", &snap_name)
-                        } else {
-                            format!("httm took a snapshot named: {}\n", &snap_name)
-                        }
-                    })
-                    .collect();
-                print_output_buf(output_buf)
+                return Err(HttmError::new(&msg).into());
             }
-        })?;
 
-        Ok(())
+            Ok(())
+        })
     }
 
     fn get_snapshot_names(
         mounts_for_files: &MountsForFiles,
-        requested_snapshot_suffix: &str,
+        snapshot_options: &SnapshotOptions,
     ) -> HttmResult<BTreeMap<String, Vec<String>>> {
         // all snapshots should have the same timestamp
-        let timestamp = get_date(
-            GLOBAL_CONFIG.requested_utc_offset,
-            &SystemTime::now(),
-            DateFormat::Timestamp,
-        );
+        let now = SystemTime::now();
 
-        let vec_snapshot_names: Vec<String> = mounts_for_files
+        let datasets: BTreeSet<String> = mounts_for_files
             .iter()
             .flat_map(|(_pathdata, datasets)| datasets)
             .map(|mount| {
-            let dataset: String = match &GLOBAL_CONFIG.dataset_collection.opt_map_of_aliases {
+            match &GLOBAL_CONFIG.dataset_collection.opt_map_of_aliases {
                 None => {
                     match GLOBAL_CONFIG.dataset_collection.map_of_datasets.inner.get(&mount.path_buf) {
                         Some(dataset_info) => {
@@ -112,21 +293,31 @@ impl TakeSnapshot {
                                 Err(HttmError::new("httm does not currently support snapshot-ing non-ZFS filesystems."))
                             }
                         }
-                        None => return Err(HttmError::new("httm was unable to parse dataset from mount!")),
+                        None => Err(HttmError::new("httm was unable to parse dataset from mount!")),
                     }
                 }
-                Some(_) => return Err(HttmError::new("httm does not currently support snapshot-ing user defined mount points.")),
-            }?;
-
-            let snapshot_name = format!(
-                "{}@snap_{}_{}",
-                dataset,
-                timestamp,
-                requested_snapshot_suffix,
-            );
-
-            Ok(snapshot_name)
-        }).collect::<Result<Vec<String>, HttmError>>()?;
+                Some(_) => Err(HttmError::new("httm does not currently support snapshot-ing user defined mount points.")),
+            }
+        }).collect::<Result<BTreeSet<String>, HttmError>>()?;
+
+        // a recursive snapshot of a dataset already includes its children, and
+        // ZFS will refuse to take a snapshot of a child twice in the same command
+        let vec_snapshot_names: Vec<String> = datasets
+            .iter()
+            .filter(|dataset| {
+                !snapshot_options.recursive
+                    || !datasets.iter().any(|ancestor| {
+                        dataset
+                            .strip_prefix(ancestor.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                    })
+            })
+            .map(|dataset| {
+                snapshot_options
+                    .template
+                    .expand(dataset, &snapshot_options.suffix, &now)
+            })
+            .collect::<HttmResult<Vec<String>>>()?;
 
         // why all this garbage with BTreeMaps, etc.? ZFS will not allow one to take snapshots
         // with the same name, at the same time, across pools.  Since we don't really care, we break
@@ -0,0 +1,498 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::path::{Path, PathBuf};
+
+use hashbrown::{HashMap, HashSet};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::config::generate::{PrintMode, TreeDiffOptions};
+use crate::data::paths::PathData;
+use crate::exec::roll_forward::DiffType;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{display_human_size, get_delimiter, print_output_buf};
+use crate::lookup::dir_contents::{DirContents, EntrySummary};
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::lookup::versions::VersionsMap;
+use crate::{BTRFS_SNAPPER_SUFFIX, GLOBAL_CONFIG};
+
+// one side of a tree diff: a version of the requested directory
+struct TreeVersion {
+    path: PathBuf,
+    // None is the live version
+    opt_snap_name: Option<String>,
+}
+
+impl TreeVersion {
+    fn get_label(&self) -> &str {
+        match &self.opt_snap_name {
+            Some(snap_name) => snap_name.as_str(),
+            None => "live version",
+        }
+    }
+}
+
+struct TreeDiffEntry {
+    relative_path: PathBuf,
+    diff_type: DiffType,
+    opt_old: Option<EntrySummary>,
+    opt_new: Option<EntrySummary>,
+}
+
+pub struct TreeDiff {
+    dir: PathBuf,
+    from: TreeVersion,
+    to: TreeVersion,
+    entries: Vec<TreeDiffEntry>,
+}
+
+impl TreeDiff {
+    pub fn exec(tree_diff_options: &TreeDiffOptions) -> HttmResult<()> {
+        let dir_pathdata = PathData::from(tree_diff_options.dir.as_path());
+
+        let from = Self::get_snap_version(&dir_pathdata, &tree_diff_options.snap_a)?;
+        let to = match &tree_diff_options.opt_snap_b {
+            Some(snap_b) => Self::get_snap_version(&dir_pathdata, snap_b)?,
+            None => {
+                if !dir_pathdata.path_buf.is_dir() {
+                    let msg = format!(
+                        "The live version of {:?} is not a directory, or does not exist.",
+                        dir_pathdata.path_buf
+                    );
+                    return Err(HttmError::new(&msg).into());
+                }
+
+                TreeVersion {
+                    path: dir_pathdata.path_buf.clone(),
+                    opt_snap_name: None,
+                }
+            }
+        };
+
+        let (contents_from, contents_to) = rayon::join(
+            || DirContents::new(&from.path),
+            || DirContents::new(&to.path),
+        );
+
+        let tree_diff = TreeDiff {
+            dir: dir_pathdata.path_buf,
+            entries: Self::get_entries(&contents_from, &contents_to),
+            from,
+            to,
+        };
+
+        let output_buf = if GLOBAL_CONFIG.opt_json {
+            tree_diff.to_json()
+        } else {
+            tree_diff.to_string()
+        };
+
+        print_output_buf(output_buf)
+    }
+
+    fn get_snap_version(dir_pathdata: &PathData, requested: &str) -> HttmResult<TreeVersion> {
+        let snaps_selected_for_search = GLOBAL_CONFIG
+            .dataset_collection
+            .snaps_selected_for_search
+            .get_value();
+
+        let opt_version = VersionsMap::get_search_bundles(dir_pathdata, snaps_selected_for_search)
+            .find_map(|search_bundle| {
+                search_bundle.snap_mounts.iter().find_map(|snap_mount| {
+                    Self::get_snap_name_if_requested(snap_mount, requested)
+                        .map(|snap_name| (snap_mount.join(search_bundle.relative_path), snap_name))
+                })
+            });
+
+        match opt_version {
+            Some((path, _snap_name)) if !path.is_dir() => {
+                let msg = format!(
+                    "The version of {:?} on snapshot {requested:?} is not a directory, or does not exist.",
+                    dir_pathdata.path_buf
+                );
+                Err(HttmError::new(&msg).into())
+            }
+            Some((path, snap_name)) => Ok(TreeVersion {
+                path,
+                opt_snap_name: Some(snap_name),
+            }),
+            None => {
+                let msg = format!(
+                    "httm could not find a snapshot named {requested:?} for {:?}.  \
+                    Perhaps try LIST_SNAPS to view the names of the available snapshots.",
+                    dir_pathdata.path_buf
+                );
+                Err(HttmError::new(&msg).into())
+            }
+        }
+    }
+
+    // a snapshot may be requested by its full name, by the final component
+    // of its snap mount (or, for snapper, its number), or by its snap mount path
+    fn get_snap_name_if_requested(snap_mount: &Path, requested: &str) -> Option<String> {
+        let snap_name = DeconstructedSnapPathData::from_snap_mount(&PathData::from(snap_mount))
+            .map(|deconstructed| deconstructed.snap_name)
+            .unwrap_or_else(|| snap_mount.to_string_lossy().into_owned());
+
+        let opt_final_component = if snap_mount.ends_with(BTRFS_SNAPPER_SUFFIX) {
+            snap_mount.parent().and_then(Path::file_name)
+        } else {
+            snap_mount.file_name()
+        };
+
+        let is_requested = snap_name == requested
+            || snap_mount == Path::new(requested)
+            || opt_final_component.is_some_and(|final_component| final_component == requested);
+
+        is_requested.then_some(snap_name)
+    }
+
+    fn get_entries(contents_from: &DirContents, contents_to: &DirContents) -> Vec<TreeDiffEntry> {
+        let mut removed: Vec<(&PathBuf, &EntrySummary)> = contents_from
+            .inner
+            .iter()
+            .filter(|(relative_path, _)| !contents_to.inner.contains_key(*relative_path))
+            .collect();
+
+        let mut created: HashMap<&PathBuf, &EntrySummary> = contents_to
+            .inner
+            .iter()
+            .filter(|(relative_path, _)| !contents_from.inner.contains_key(*relative_path))
+            .collect();
+
+        // zfs diff semantics: a rename is an entry removed under one name, and created
+        // under another, which is the same filesystem object.  inode numbers aren't
+        // meaningful when a snapshot dir is aliased to a local dir, so we don't try
+        let mut renamed: HashMap<&PathBuf, &PathBuf> = HashMap::new();
+
+        if GLOBAL_CONFIG
+            .dataset_collection
+            .opt_map_of_aliases
+            .is_none()
+        {
+            let created_by_inode: HashMap<u64, &PathBuf> = created
+                .iter()
+                .map(|(relative_path, entry)| (entry.inode, *relative_path))
+                .collect();
+
+            removed.iter().for_each(|(old_path, old_entry)| {
+                let opt_new_path = created_by_inode.get(&old_entry.inode).filter(|new_path| {
+                    created
+                        .get(*new_path)
+                        .is_some_and(|new_entry| new_entry.is_same_inode(old_entry))
+                });
+
+                if let Some(new_path) = opt_new_path {
+                    renamed.insert(old_path, new_path);
+                }
+            });
+        }
+
+        let mut entries: Vec<TreeDiffEntry> = Vec::new();
+
+        let renamed_to: HashSet<&PathBuf> = renamed.values().copied().collect();
+
+        removed.retain(|(old_path, _)| !renamed.contains_key(old_path));
+        created.retain(|new_path, _| !renamed_to.contains(new_path));
+
+        renamed
+            .iter()
+            // entries beneath a renamed directory are renamed along with it,
+            // so only display the rename of the directory
+            .filter(|(old_path, new_path)| {
+                let opt_expected_path =
+                    old_path
+                        .parent()
+                        .zip(old_path.file_name())
+                        .and_then(|(parent, file_name)| {
+                            renamed
+                                .get(&parent.to_path_buf())
+                                .map(|renamed_parent| renamed_parent.join(file_name))
+                        });
+
+                opt_expected_path.as_ref() != Some(**new_path)
+            })
+            .for_each(|(old_path, new_path)| {
+                entries.push(TreeDiffEntry {
+                    relative_path: old_path.to_path_buf(),
+                    diff_type: DiffType::Renamed(new_path.to_path_buf()),
+                    opt_old: contents_from.inner.get(*old_path).copied(),
+                    opt_new: contents_to.inner.get(*new_path).copied(),
+                })
+            });
+
+        removed.into_iter().for_each(|(relative_path, entry)| {
+            entries.push(TreeDiffEntry {
+                relative_path: relative_path.clone(),
+                diff_type: DiffType::Removed,
+                opt_old: Some(*entry),
+                opt_new: None,
+            })
+        });
+
+        created.into_iter().for_each(|(relative_path, entry)| {
+            entries.push(TreeDiffEntry {
+                relative_path: relative_path.clone(),
+                diff_type: DiffType::Created,
+                opt_old: None,
+                opt_new: Some(*entry),
+            })
+        });
+
+        contents_to
+            .inner
+            .iter()
+            .filter_map(|(relative_path, new_entry)| {
+                contents_from
+                    .inner
+                    .get(relative_path)
+                    .filter(|old_entry| new_entry.is_modified(old_entry))
+                    .map(|old_entry| (relative_path, old_entry, new_entry))
+            })
+            .for_each(|(relative_path, old_entry, new_entry)| {
+                entries.push(TreeDiffEntry {
+                    relative_path: relative_path.clone(),
+                    diff_type: DiffType::Modified,
+                    opt_old: Some(*old_entry),
+                    opt_new: Some(*new_entry),
+                })
+            });
+
+        entries.sort_unstable_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+        entries
+    }
+
+    fn to_json(&self) -> String {
+        let res = match GLOBAL_CONFIG.print_mode {
+            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                serde_json::to_string(self)
+            }
+            PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+        };
+
+        match res {
+            Ok(s) => {
+                let delimiter = get_delimiter();
+                format!("{s}{delimiter}")
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                std::process::exit(1)
+            }
+        }
+    }
+}
+
+impl TreeDiffEntry {
+    fn get_symbol(&self) -> &'static str {
+        match self.diff_type {
+            DiffType::Created => "+",
+            DiffType::Removed => "-",
+            DiffType::Modified => "M",
+            DiffType::Renamed(_) => "R",
+        }
+    }
+
+    fn get_type(&self) -> &'static str {
+        match self.diff_type {
+            DiffType::Created => "created",
+            DiffType::Removed => "removed",
+            DiffType::Modified => "modified",
+            DiffType::Renamed(_) => "renamed",
+        }
+    }
+
+    // the size of the entry after the change, or, if removed, before
+    fn get_size(&self) -> Option<u64> {
+        self.opt_new
+            .or(self.opt_old)
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| entry.size)
+    }
+
+    fn get_old_size(&self) -> Option<u64> {
+        match self.diff_type {
+            DiffType::Modified => self
+                .opt_old
+                .filter(|entry| !entry.is_dir)
+                .map(|entry| entry.size),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TreeDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = get_delimiter();
+
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            return self.entries.iter().try_for_each(|entry| {
+                let path = self.dir.join(&entry.relative_path);
+                let size = entry.get_size().unwrap_or_default();
+
+                match &entry.diff_type {
+                    DiffType::Renamed(new_path) => write!(
+                        f,
+                        "{}\t{}\t{size}\t{}{delimiter}",
+                        entry.get_symbol(),
+                        path.to_string_lossy(),
+                        self.dir.join(new_path).to_string_lossy()
+                    ),
+                    _ => write!(
+                        f,
+                        "{}\t{}\t{size}{delimiter}",
+                        entry.get_symbol(),
+                        path.to_string_lossy()
+                    ),
+                }
+            });
+        }
+
+        writeln!(f, "Tree diff: {:?}", self.dir)?;
+        writeln!(f, "from: {} ({:?})", self.from.get_label(), self.from.path)?;
+        writeln!(f, "to:   {} ({:?})", self.to.get_label(), self.to.path)?;
+        writeln!(f, "{:─<80}", "")?;
+
+        if self.entries.is_empty() {
+            writeln!(f, "No differences were found between these versions.")?;
+        }
+
+        self.entries.iter().try_for_each(|entry| {
+            let path = self.dir.join(&entry.relative_path);
+
+            let size = match (entry.get_old_size(), entry.get_size()) {
+                (Some(old_size), Some(new_size)) => format!(
+                    "{} -> {}",
+                    display_human_size(old_size),
+                    display_human_size(new_size)
+                ),
+                (None, Some(size)) => display_human_size(size),
+                _ => "-".to_owned(),
+            };
+
+            match &entry.diff_type {
+                DiffType::Renamed(new_path) => writeln!(
+                    f,
+                    "{}  {size:<24}  {path:?} -> {:?}",
+                    entry.get_symbol(),
+                    self.dir.join(new_path)
+                ),
+                _ => writeln!(f, "{}  {size:<24}  {path:?}", entry.get_symbol()),
+            }
+        })?;
+
+        let count = |type_name: &str| {
+            self.entries
+                .iter()
+                .filter(|entry| entry.get_type() == type_name)
+                .count()
+        };
+
+        writeln!(f, "{:─<80}", "")?;
+        writeln!(
+            f,
+            "{} created, {} removed, {} modified, {} renamed.",
+            count("created"),
+            count("removed"),
+            count("modified"),
+            count("renamed")
+        )
+    }
+}
+
+impl Serialize for TreeDiff {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct EntryWithDir<'a> {
+            dir: &'a Path,
+            entry: &'a TreeDiffEntry,
+        }
+
+        impl<'a> Serialize for EntryWithDir<'a> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state = serializer.serialize_struct("TreeDiffEntry", 5)?;
+
+                let opt_new_path = match &self.entry.diff_type {
+                    DiffType::Renamed(new_path) => Some(self.dir.join(new_path)),
+                    _ => None,
+                };
+
+                state.serialize_field("type", self.entry.get_type())?;
+                state.serialize_field("path", &self.dir.join(&self.entry.relative_path))?;
+                state.serialize_field("new_path", &opt_new_path)?;
+
+                if matches!(
+                    GLOBAL_CONFIG.print_mode,
+                    PrintMode::RawNewline | PrintMode::RawZero
+                ) {
+                    state.serialize_field("size", &self.entry.get_size())?;
+                    state.serialize_field("old_size", &self.entry.get_old_size())?;
+                } else {
+                    state
+                        .serialize_field("size", &self.entry.get_size().map(display_human_size))?;
+                    state.serialize_field(
+                        "old_size",
+                        &self.entry.get_old_size().map(display_human_size),
+                    )?;
+                }
+
+                state.end()
+            }
+        }
+
+        struct VersionLabel<'a>(&'a TreeVersion);
+
+        impl<'a> Serialize for VersionLabel<'a> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state = serializer.serialize_struct("TreeVersion", 2)?;
+
+                state.serialize_field("path", &self.0.path)?;
+                state.serialize_field("snapshot_name", &self.0.opt_snap_name)?;
+                state.end()
+            }
+        }
+
+        let entries: Vec<EntryWithDir> = self
+            .entries
+            .iter()
+            .map(|entry| EntryWithDir {
+                dir: &self.dir,
+                entry,
+            })
+            .collect();
+
+        let mut state = serializer.serialize_struct("TreeDiff", 4)?;
+
+        state.serialize_field("path", &self.dir)?;
+        state.serialize_field("from", &VersionLabel(&self.from))?;
+        state.serialize_field("to", &VersionLabel(&self.to))?;
+        state.serialize_field("tree_diff", &entries)?;
+        state.end()
+    }
+}
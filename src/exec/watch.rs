@@ -0,0 +1,357 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::ffi::OsString;
+use std::fs::read_dir;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use hashbrown::{HashMap, HashSet};
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, InotifyEvent, WatchDescriptor};
+
+use crate::config::generate::{MountDisplay, SnapshotOptions, WatchOptions};
+use crate::data::paths::PathData;
+use crate::exec::snapshot::TakeSnapshot;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::print_output_buf;
+use crate::lookup::file_mounts::MountsForFiles;
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, GLOBAL_CONFIG, ZFS_HIDDEN_DIRECTORY};
+
+// how often we wake, when there are no events, to check whether any change has gone quiet
+const POLL_INTERVAL_MS: i32 = 1_000;
+const ONE_HOUR: Duration = Duration::from_secs(60 * 60);
+
+// a file is watched via its parent directory, as many programs save a file
+// by replacing it, which would remove a watch on the file itself
+//
+// inotify returns the same watch descriptor each time a dir is watched, so one target
+// may be both a recursively watched dir and the parent of several watched files
+struct WatchTarget {
+    dir: PathBuf,
+    file_names: HashSet<OsString>,
+    is_recursive: bool,
+}
+
+pub struct WatchPaths<'a> {
+    options: &'a WatchOptions,
+    inotify: Inotify,
+    zfs_command: PathBuf,
+    targets: HashMap<WatchDescriptor, WatchTarget>,
+    // key: dir, val: the dataset mounts upon which that dir resides
+    dataset_cache: HashMap<PathBuf, Vec<PathBuf>>,
+    // key: dataset mount, val: time of the most recent change
+    pending: HashMap<PathBuf, Instant>,
+    // key: dataset mount, val: time of the most recent snapshot
+    last_snapshot: HashMap<PathBuf, Instant>,
+    // times of each snapshot taken within the last hour
+    recent_snapshots: VecDeque<Instant>,
+    is_hourly_limit_warned: bool,
+}
+
+impl<'a> WatchPaths<'a> {
+    pub fn exec(options: &'a WatchOptions) -> HttmResult<()> {
+        let mut watch_paths = Self::new(options)?;
+
+        GLOBAL_CONFIG
+            .paths
+            .iter()
+            .try_for_each(|pathdata| watch_paths.add_path(pathdata))?;
+
+        eprintln!(
+            "httm is watching {} path/s, and will snapshot changed datasets after {:?} without further changes.",
+            GLOBAL_CONFIG.paths.len(),
+            options.debounce
+        );
+
+        loop {
+            let mut poll_fds = [PollFd::new(
+                watch_paths.inotify.as_raw_fd(),
+                PollFlags::POLLIN,
+            )];
+
+            match poll(&mut poll_fds, POLL_INTERVAL_MS) {
+                Ok(_) | Err(Errno::EINTR) => {}
+                Err(err) => return Err(err.into()),
+            }
+
+            match watch_paths.inotify.read_events() {
+                Ok(events) => events
+                    .into_iter()
+                    .for_each(|event| watch_paths.handle_event(event)),
+                Err(Errno::EAGAIN) => {}
+                Err(err) => return Err(err.into()),
+            }
+
+            watch_paths.snapshot_ready()?;
+        }
+    }
+
+    fn new(options: &'a WatchOptions) -> HttmResult<Self> {
+        let zfs_command = TakeSnapshot::get_zfs_command()?;
+        let inotify = Inotify::init(InitFlags::IN_CLOEXEC | InitFlags::IN_NONBLOCK)?;
+
+        Ok(Self {
+            options,
+            inotify,
+            zfs_command,
+            targets: HashMap::new(),
+            dataset_cache: HashMap::new(),
+            pending: HashMap::new(),
+            last_snapshot: HashMap::new(),
+            recent_snapshots: VecDeque::new(),
+            is_hourly_limit_warned: false,
+        })
+    }
+
+    fn watch_flags() -> AddWatchFlags {
+        AddWatchFlags::IN_MODIFY
+            | AddWatchFlags::IN_CLOSE_WRITE
+            | AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MOVED_FROM
+            | AddWatchFlags::IN_MOVED_TO
+    }
+
+    fn add_path(&mut self, pathdata: &PathData) -> HttmResult<()> {
+        let path = pathdata.path_buf.as_path();
+
+        if path.is_dir() {
+            self.add_dir_recursive(path);
+            return Ok(());
+        }
+
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(file_name)) if pathdata.metadata.is_some() => {
+                self.add_watch(parent, Some(file_name.to_os_string()));
+                Ok(())
+            }
+            _ => {
+                let msg = format!("httm could not watch a path which does not exist: {path:?}");
+                Err(HttmError::new(&msg).into())
+            }
+        }
+    }
+
+    fn add_watch(&mut self, dir: &Path, opt_file_name: Option<OsString>) {
+        match self.inotify.add_watch(dir, Self::watch_flags()) {
+            Ok(watch_descriptor) => {
+                let target = self
+                    .targets
+                    .entry(watch_descriptor)
+                    .or_insert_with(|| WatchTarget {
+                        dir: dir.to_path_buf(),
+                        file_names: HashSet::new(),
+                        is_recursive: false,
+                    });
+
+                match opt_file_name {
+                    Some(file_name) => {
+                        target.file_names.insert(file_name);
+                    }
+                    None => target.is_recursive = true,
+                }
+            }
+            // most likely, the user has exhausted "fs.inotify.max_user_watches"
+            Err(err) => eprintln!("WARNING: httm could not watch {dir:?}: {err}"),
+        }
+    }
+
+    // inotify watches are not recursive, so we must add a watch for every directory
+    fn add_dir_recursive(&mut self, dir: &Path) {
+        self.add_watch(dir, None);
+
+        let read_dir = match read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(_) => return,
+        };
+
+        read_dir
+            .flatten()
+            .filter(|dir_entry| {
+                dir_entry
+                    .file_type()
+                    .map(|file_type| file_type.is_dir())
+                    .unwrap_or(false)
+            })
+            // don't watch snapshots
+            .filter(|dir_entry| {
+                let file_name = dir_entry.file_name();
+                file_name != ZFS_HIDDEN_DIRECTORY && file_name != BTRFS_SNAPPER_HIDDEN_DIRECTORY
+            })
+            .for_each(|dir_entry| self.add_dir_recursive(&dir_entry.path()));
+    }
+
+    fn handle_event(&mut self, event: InotifyEvent) {
+        // the kernel queue overflowed, and we may have missed any change
+        if event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+            let dirs: Vec<PathBuf> = self
+                .targets
+                .values()
+                .map(|target| target.dir.clone())
+                .collect();
+            dirs.iter().for_each(|dir| self.mark_changed(dir));
+            return;
+        }
+
+        if event.mask.contains(AddWatchFlags::IN_IGNORED) {
+            self.targets.remove(&event.wd);
+            return;
+        }
+
+        let target = match self.targets.get(&event.wd) {
+            Some(target) => target,
+            None => return,
+        };
+
+        if let Some(event_name) = &event.name {
+            if !target.is_recursive && !target.file_names.contains(event_name) {
+                return;
+            }
+        }
+
+        let dir = target.dir.clone();
+        let is_recursive = target.is_recursive;
+
+        // a new directory within a recursively watched directory must also be watched
+        if is_recursive
+            && event.mask.contains(AddWatchFlags::IN_ISDIR)
+            && event
+                .mask
+                .intersects(AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVED_TO)
+        {
+            if let Some(name) = &event.name {
+                self.add_dir_recursive(&dir.join(name));
+            }
+        }
+
+        self.mark_changed(&dir);
+    }
+
+    fn mark_changed(&mut self, dir: &Path) {
+        let dataset_mounts = self
+            .dataset_cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                MountsForFiles::from_raw_paths(&[PathData::from(dir)], &MountDisplay::Target)
+                    .values()
+                    .flatten()
+                    .map(|mount| mount.path_buf.clone())
+                    .collect()
+            });
+
+        let now = Instant::now();
+
+        dataset_mounts.iter().for_each(|mount| {
+            self.pending.insert(mount.clone(), now);
+        });
+    }
+
+    fn snapshot_ready(&mut self) -> HttmResult<()> {
+        let now = Instant::now();
+
+        while let Some(oldest) = self.recent_snapshots.front() {
+            if now.duration_since(*oldest) < ONE_HOUR {
+                break;
+            }
+            self.recent_snapshots.pop_front();
+        }
+
+        // a dataset is ready when its changes have gone quiet, and it was not snapshot too recently
+        let mut ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_mount, last_change)| {
+                now.duration_since(**last_change) >= self.options.debounce
+            })
+            .filter(
+                |(mount, _last_change)| match self.last_snapshot.get(*mount) {
+                    Some(last_snapshot) => {
+                        now.duration_since(*last_snapshot) >= self.options.rate_limit
+                    }
+                    None => true,
+                },
+            )
+            .map(|(mount, _last_change)| mount.clone())
+            .collect();
+
+        if ready.is_empty() {
+            return Ok(());
+        }
+
+        let remaining = self
+            .options
+            .max_per_hour
+            .saturating_sub(self.recent_snapshots.len());
+
+        if remaining == 0 {
+            if !self.is_hourly_limit_warned {
+                eprintln!(
+                    "WARNING: httm has taken the maximum of {} snapshot/s this hour.  Further snapshots will wait.",
+                    self.options.max_per_hour
+                );
+                self.is_hourly_limit_warned = true;
+            }
+            return Ok(());
+        }
+
+        ready.sort();
+        ready.truncate(remaining);
+
+        ready.iter().for_each(|mount| {
+            self.last_snapshot.insert(mount.clone(), now);
+            self.recent_snapshots.push_back(now);
+        });
+
+        let inner: BTreeMap<PathData, Vec<PathData>> = ready
+            .iter()
+            .map(|mount| {
+                let pathdata = PathData::from(mount.as_path());
+                (pathdata.clone(), vec![pathdata])
+            })
+            .collect();
+
+        let mounts_for_files = MountsForFiles {
+            inner,
+            mount_display: &MountDisplay::Target,
+        };
+
+        // a failed snapshot shouldn't stop the watch, and counts against the rate limits,
+        // so we don't keep retrying a snapshot which will never succeed, but its changes
+        // remain pending, to be retried once the rate limits allow
+        match TakeSnapshot::snapshot_mounts(
+            &self.zfs_command,
+            &mounts_for_files,
+            &SnapshotOptions::from_suffix(&self.options.suffix),
+        ) {
+            Ok(snapshot_names) => {
+                ready.iter().for_each(|mount| {
+                    self.pending.remove(mount);
+                });
+                self.is_hourly_limit_warned = false;
+                print_output_buf(TakeSnapshot::format_snapshot_names(&snapshot_names))
+            }
+            Err(error) => {
+                eprintln!("WARNING: {error}");
+                Ok(())
+            }
+        }
+    }
+}
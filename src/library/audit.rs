@@ -0,0 +1,165 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::{create_dir_all, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::library::hooks::HookOperation;
+use crate::library::results::HttmResult;
+use crate::GLOBAL_CONFIG;
+
+pub const DEFAULT_AUDIT_LOG: &str = "/var/log/httm/audit.jsonl";
+const SYSLOG_SOCKET: &str = "/dev/log";
+// facility "authpriv" (10), severity "notice" (5)
+const SYSLOG_PRIORITY: u8 = 10 * 8 + 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditLog {
+    JsonLines(PathBuf),
+    Syslog,
+}
+
+impl AuditLog {
+    // an audit log should never stop an operation, nor hide its result, so failures only warn
+    pub fn record<T>(
+        &self,
+        operation: HookOperation,
+        paths: &[PathBuf],
+        snapshot_names: &[String],
+        opt_bytes_written: Option<u64>,
+        result: &HttmResult<T>,
+    ) {
+        let entry = AuditEntry {
+            operation,
+            paths,
+            snapshot_names,
+            opt_bytes_written,
+            opt_error: result.as_ref().err().map(|error| error.to_string()),
+        };
+
+        let res = match serde_json::to_string(&entry) {
+            Ok(json) => match self {
+                AuditLog::JsonLines(log_path) => Self::append(log_path, &json),
+                AuditLog::Syslog => Self::send_syslog(&json),
+            },
+            Err(error) => Err(error.into()),
+        };
+
+        if let Err(error) = res {
+            eprintln!("WARNING: httm could not write to the audit log: {error}");
+        }
+    }
+
+    fn append(log_path: &Path, json: &str) -> HttmResult<()> {
+        if let Some(parent) = log_path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        let mut log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o640)
+            .open(log_path)?;
+
+        // a single write per line, so concurrent writers can't interleave within a line
+        log_file.write_all(format!("{json}\n").as_bytes())?;
+
+        Ok(())
+    }
+
+    fn send_syslog(json: &str) -> HttmResult<()> {
+        let socket = UnixDatagram::unbound()?;
+
+        let message = format!("<{SYSLOG_PRIORITY}>httm[{}]: {json}", std::process::id());
+
+        socket.send_to(message.as_bytes(), SYSLOG_SOCKET)?;
+
+        Ok(())
+    }
+}
+
+// shorthand for the audit log the user has configured, if any
+pub fn audit<T>(
+    operation: HookOperation,
+    paths: &[PathBuf],
+    snapshot_names: &[String],
+    opt_bytes_written: Option<u64>,
+    result: &HttmResult<T>,
+) {
+    if let Some(audit_log) = &GLOBAL_CONFIG.opt_audit_log {
+        audit_log.record(operation, paths, snapshot_names, opt_bytes_written, result)
+    }
+}
+
+struct AuditEntry<'a> {
+    operation: HookOperation,
+    paths: &'a [PathBuf],
+    snapshot_names: &'a [String],
+    opt_bytes_written: Option<u64>,
+    opt_error: Option<String>,
+}
+
+impl<'a> Serialize for AuditEntry<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("AuditEntry", 12)?;
+
+        let timestamp = OffsetDateTime::now_utc().format(&Rfc3339).ok();
+
+        let opt_hostname = nix::unistd::gethostname()
+            .ok()
+            .map(|hostname| hostname.to_string_lossy().into_owned());
+
+        let opt_user = std::env::var("USER")
+            .or_else(|_| std::env::var("LOGNAME"))
+            .ok();
+
+        let command_line: Vec<String> = std::env::args_os()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+
+        let outcome = if self.opt_error.is_some() {
+            "failure"
+        } else {
+            "success"
+        };
+
+        state.serialize_field("timestamp", &timestamp)?;
+        state.serialize_field("uid", &nix::unistd::getuid().as_raw())?;
+        state.serialize_field("euid", &nix::unistd::geteuid().as_raw())?;
+        state.serialize_field("user", &opt_user)?;
+        state.serialize_field("hostname", &opt_hostname)?;
+        state.serialize_field("command_line", &command_line)?;
+        state.serialize_field("operation", self.operation.as_str())?;
+        state.serialize_field("paths", self.paths)?;
+        state.serialize_field("snapshot_names", self.snapshot_names)?;
+        state.serialize_field("bytes_written", &self.opt_bytes_written)?;
+        state.serialize_field("outcome", outcome)?;
+        state.serialize_field("error", &self.opt_error)?;
+        state.end()
+    }
+}
@@ -0,0 +1,346 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::{create_dir_all, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use hashbrown::HashSet;
+use sha1::{Digest, Sha1};
+use tar::{Builder, EntryType, Header};
+
+use crate::library::results::{HttmError, HttmResult};
+
+// we write the few git objects we require natively, instead of requiring a git executable or libgit2
+
+pub type ObjectId = [u8; 20];
+
+pub fn to_hex(object_id: &ObjectId) -> String {
+    object_id.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub enum GitObjectType {
+    Blob,
+    Tree,
+    Commit,
+}
+
+impl GitObjectType {
+    fn as_str(&self) -> &str {
+        match self {
+            GitObjectType::Blob => "blob",
+            GitObjectType::Tree => "tree",
+            GitObjectType::Commit => "commit",
+        }
+    }
+}
+
+pub struct TreeEntry {
+    pub mode: &'static str,
+    pub name: Vec<u8>,
+    pub object_id: ObjectId,
+}
+
+impl TreeEntry {
+    pub const MODE_FILE: &'static str = "100644";
+    pub const MODE_EXECUTABLE: &'static str = "100755";
+    pub const MODE_SYMLINK: &'static str = "120000";
+    pub const MODE_DIR: &'static str = "40000";
+
+    // git sorts tree entries by name, as though directory names ended with a "/"
+    fn sort_key(&self) -> Vec<u8> {
+        let mut key = self.name.clone();
+        if self.mode == Self::MODE_DIR {
+            key.push(b'/');
+        }
+        key
+    }
+}
+
+// where the files of the bare repository end up: either a directory or a tar archive
+pub enum RepoWriter {
+    Dir(PathBuf),
+    Tar(TarWriter),
+}
+
+impl RepoWriter {
+    fn write_file(&mut self, relpath: &str, contents: &[u8]) -> HttmResult<()> {
+        match self {
+            RepoWriter::Dir(root) => {
+                let path = root.join(relpath);
+                if let Some(parent) = path.parent() {
+                    create_dir_all(parent)?;
+                }
+                let mut file = File::create(path)?;
+                file.write_all(contents)?;
+                Ok(())
+            }
+            RepoWriter::Tar(tar_writer) => tar_writer.write_file(relpath, contents),
+        }
+    }
+
+    fn finish(self) -> HttmResult<()> {
+        match self {
+            RepoWriter::Dir(_) => Ok(()),
+            RepoWriter::Tar(tar_writer) => tar_writer.finish(),
+        }
+    }
+}
+
+pub struct BareRepo {
+    writer: RepoWriter,
+    written: HashSet<ObjectId>,
+}
+
+impl BareRepo {
+    pub const BRANCH: &'static str = "master";
+
+    pub fn new(mut writer: RepoWriter) -> HttmResult<Self> {
+        writer.write_file(
+            "HEAD",
+            format!("ref: refs/heads/{}\n", Self::BRANCH).as_bytes(),
+        )?;
+        writer.write_file(
+            "config",
+            b"[core]\n\trepositoryformatversion = 0\n\tfilemode = true\n\tbare = true\n",
+        )?;
+        writer.write_file("description", b"Snapshot history exported by httm\n")?;
+
+        Ok(Self {
+            writer,
+            written: HashSet::new(),
+        })
+    }
+
+    pub fn write_object(
+        &mut self,
+        object_type: GitObjectType,
+        contents: &[u8],
+    ) -> HttmResult<ObjectId> {
+        let mut object = format!("{} {}\0", object_type.as_str(), contents.len()).into_bytes();
+        object.extend_from_slice(contents);
+
+        let object_id: ObjectId = Sha1::digest(&object).into();
+
+        if self.written.insert(object_id) {
+            let hex = to_hex(&object_id);
+            let relpath = format!("objects/{}/{}", &hex[..2], &hex[2..]);
+
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&object)?;
+
+            self.writer.write_file(&relpath, &encoder.finish()?)?;
+        }
+
+        Ok(object_id)
+    }
+
+    pub fn write_tree(&mut self, mut entries: Vec<TreeEntry>) -> HttmResult<ObjectId> {
+        // git will refuse to read a tree with an entry it can't check out
+        if let Some(entry) = entries
+            .iter()
+            .find(|entry| entry.name.is_empty() || entry.name.contains(&b'/'))
+        {
+            let msg = format!(
+                "httm can't export an entry named {:?} to git, as a git tree entry requires a non-empty name without a '/'",
+                String::from_utf8_lossy(&entry.name)
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        entries.sort_by_key(|entry| entry.sort_key());
+
+        let contents: Vec<u8> = entries
+            .iter()
+            .flat_map(|entry| {
+                let mut bytes = format!("{} ", entry.mode).into_bytes();
+                bytes.extend_from_slice(&entry.name);
+                bytes.push(0);
+                bytes.extend_from_slice(&entry.object_id);
+                bytes
+            })
+            .collect();
+
+        self.write_object(GitObjectType::Tree, &contents)
+    }
+
+    // "signature" is the author and committer, like "httm <httm@localhost> 1672531200 +0000"
+    pub fn write_commit(
+        &mut self,
+        tree: &ObjectId,
+        opt_parent: Option<&ObjectId>,
+        signature: &str,
+        message: &str,
+    ) -> HttmResult<ObjectId> {
+        let mut contents = format!("tree {}\n", to_hex(tree));
+
+        if let Some(parent) = opt_parent {
+            contents += &format!("parent {}\n", to_hex(parent));
+        }
+
+        contents += &format!("author {signature}\ncommitter {signature}\n\n{message}\n");
+
+        self.write_object(GitObjectType::Commit, contents.as_bytes())
+    }
+
+    pub fn finish(mut self, opt_head: Option<&ObjectId>) -> HttmResult<()> {
+        if let Some(head) = opt_head {
+            self.writer.write_file(
+                &format!("refs/heads/{}", Self::BRANCH),
+                format!("{}\n", to_hex(head)).as_bytes(),
+            )?;
+        }
+
+        self.writer.finish()
+    }
+}
+
+// only regular files and their parent directories are required
+pub struct TarWriter {
+    builder: Builder<BufWriter<File>>,
+    prefix: String,
+    dirs_written: HashSet<String>,
+    mtime: u64,
+}
+
+impl TarWriter {
+    pub fn new(path: &Path, prefix: &str) -> HttmResult<Self> {
+        let file = OpenOptions::new().write(true).create_new(true).open(path)?;
+
+        let mtime = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let mut tar_writer = Self {
+            builder: Builder::new(BufWriter::new(file)),
+            prefix: prefix.to_owned(),
+            dirs_written: HashSet::new(),
+            mtime,
+        };
+
+        tar_writer.write_dir(prefix)?;
+
+        Ok(tar_writer)
+    }
+
+    fn write_file(&mut self, relpath: &str, contents: &[u8]) -> HttmResult<()> {
+        let name = format!("{}/{relpath}", self.prefix);
+
+        // tar readers expect parent directories before their contents
+        let parents: Vec<String> = Path::new(&name)
+            .ancestors()
+            .skip(1)
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .map(|ancestor| ancestor.to_string_lossy().into_owned())
+            .collect();
+
+        parents
+            .into_iter()
+            .rev()
+            .try_for_each(|parent| self.write_dir(&parent))?;
+
+        self.append(&name, EntryType::Regular, 0o644, contents)
+    }
+
+    fn write_dir(&mut self, name: &str) -> HttmResult<()> {
+        if self.dirs_written.insert(name.to_owned()) {
+            self.append(name, EntryType::Directory, 0o755, &[])?;
+        }
+
+        Ok(())
+    }
+
+    fn append(
+        &mut self,
+        name: &str,
+        entry_type: EntryType,
+        mode: u32,
+        contents: &[u8],
+    ) -> HttmResult<()> {
+        let mut header = Header::new_ustar();
+        header.set_entry_type(entry_type);
+        header.set_mode(mode);
+        header.set_mtime(self.mtime);
+        header.set_size(contents.len() as u64);
+
+        self.builder
+            .append_data(&mut header, name, contents)
+            .map_err(|err| {
+                let msg = format!("httm could not write {name:?} to the tar archive");
+                HttmError::with_context(&msg, &err).into()
+            })
+    }
+
+    fn finish(self) -> HttmResult<()> {
+        self.builder.into_inner()?.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_test_repo(name: &str) -> (PathBuf, BareRepo) {
+        let dir = std::env::temp_dir().join(format!("httm-test-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+
+        let repo = BareRepo::new(RepoWriter::Dir(dir.clone())).unwrap();
+
+        (dir, repo)
+    }
+
+    #[test]
+    fn blob_has_git_object_id() {
+        let (dir, mut repo) = new_test_repo("blob");
+
+        let object_id = repo.write_object(GitObjectType::Blob, b"hello\n").unwrap();
+
+        // as given by: printf 'hello\n' | git hash-object --stdin
+        assert_eq!(
+            to_hex(&object_id),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+        assert!(dir
+            .join("objects/ce/013625030ba8dba906f756967f9e9ca394464a")
+            .exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn tree_rejects_empty_entry_name() {
+        let (dir, mut repo) = new_test_repo("tree");
+
+        let object_id = repo.write_object(GitObjectType::Blob, b"").unwrap();
+        let entries = vec![TreeEntry {
+            mode: TreeEntry::MODE_FILE,
+            name: Vec::new(),
+            object_id,
+        }];
+
+        assert!(repo.write_tree(entries).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,294 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::read_to_string;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command as ExecProcess, Stdio};
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::library::results::{HttmError, HttmResult};
+use crate::GLOBAL_CONFIG;
+
+const HOOKS_FILE_NAME: &str = "httm/hooks.conf";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookOperation {
+    Snapshot,
+    Restore,
+    Purge,
+    RollForward,
+}
+
+impl HookOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookOperation::Snapshot => "snapshot",
+            HookOperation::Restore => "restore",
+            HookOperation::Purge => "purge",
+            HookOperation::RollForward => "roll-forward",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snapshot" => Some(HookOperation::Snapshot),
+            "restore" => Some(HookOperation::Restore),
+            "purge" => Some(HookOperation::Purge),
+            "roll-forward" | "roll_forward" => Some(HookOperation::RollForward),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookType {
+    Pre,
+    Post,
+}
+
+impl HookType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HookType::Pre => "pre",
+            HookType::Post => "post",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HookCommand {
+    hook_type: HookType,
+    // None applies the hook to every operation
+    opt_operation: Option<HookOperation>,
+    command: String,
+}
+
+// commands run before and after operations which modify files or snapshots.  each is
+// given a JSON description of the operation on stdin, and a failing pre hook aborts the operation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hooks {
+    inner: Vec<HookCommand>,
+}
+
+impl Hooks {
+    pub fn new(
+        pre_commands: &[String],
+        post_commands: &[String],
+        opt_hooks_file: Option<&Path>,
+    ) -> HttmResult<Self> {
+        let mut inner: Vec<HookCommand> = Vec::new();
+
+        // a hooks file the user names must exist, but the default file is optional
+        match opt_hooks_file {
+            Some(hooks_file) => inner.extend(Self::parse_file(hooks_file)?),
+            None => {
+                if let Some(default_file) = Self::get_default_file() {
+                    if default_file.exists() {
+                        inner.extend(Self::parse_file(&default_file)?)
+                    }
+                }
+            }
+        }
+
+        inner.extend(pre_commands.iter().map(|command| HookCommand {
+            hook_type: HookType::Pre,
+            opt_operation: None,
+            command: command.clone(),
+        }));
+
+        inner.extend(post_commands.iter().map(|command| HookCommand {
+            hook_type: HookType::Post,
+            opt_operation: None,
+            command: command.clone(),
+        }));
+
+        Ok(Self { inner })
+    }
+
+    fn get_default_file() -> Option<PathBuf> {
+        if let Ok(config_home) = std::env::var("XDG_CONFIG_HOME") {
+            return Some(PathBuf::from(config_home).join(HOOKS_FILE_NAME));
+        }
+
+        std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".config").join(HOOKS_FILE_NAME))
+    }
+
+    // each line is a key and a command, like "pre.snapshot = /usr/local/bin/quiesce-db",
+    // where the key's operation may also be "*", for every operation
+    fn parse_file(hooks_file: &Path) -> HttmResult<Vec<HookCommand>> {
+        let contents = read_to_string(hooks_file).map_err(|err| {
+            let msg = format!("httm could not read the hooks file {hooks_file:?}");
+            HttmError::with_context(&msg, &err)
+        })?;
+
+        contents
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line.trim()))
+            .filter(|(_line_num, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(line_num, line)| {
+                let opt_hook = line.split_once('=').and_then(|(key, command)| {
+                    let (hook_type, operation) = key.trim().split_once('.')?;
+
+                    let hook_type = match hook_type {
+                        "pre" => HookType::Pre,
+                        "post" => HookType::Post,
+                        _ => return None,
+                    };
+
+                    let opt_operation = match operation {
+                        "*" => None,
+                        name => Some(HookOperation::from_name(name)?),
+                    };
+
+                    let command = command.trim();
+
+                    if command.is_empty() {
+                        return None;
+                    }
+
+                    Some(HookCommand {
+                        hook_type,
+                        opt_operation,
+                        command: command.to_owned(),
+                    })
+                });
+
+                opt_hook.ok_or_else(|| {
+                    let msg = format!(
+                        "httm could not parse line {line_num} of the hooks file {hooks_file:?}.  \
+                        Each line should be like \"pre.snapshot = command\", where \"pre\" may also be \"post\", \
+                        and \"snapshot\" may also be \"restore\", \"purge\", \"roll-forward\", or \"*\" for any operation."
+                    );
+                    HttmError::new(&msg).into()
+                })
+            })
+            .collect()
+    }
+
+    pub fn run(
+        &self,
+        hook_type: HookType,
+        operation: HookOperation,
+        paths: &[PathBuf],
+        snapshot_names: &[String],
+    ) -> HttmResult<()> {
+        let event = HookEvent {
+            hook_type,
+            operation,
+            paths,
+            snapshot_names,
+        };
+
+        self.inner
+            .iter()
+            .filter(|hook| hook.hook_type == hook_type)
+            .filter(|hook| match hook.opt_operation {
+                Some(hook_operation) => hook_operation == operation,
+                None => true,
+            })
+            .try_for_each(|hook| match Self::exec_hook(&hook.command, &event) {
+                Ok(_) => Ok(()),
+                Err(error) => match hook_type {
+                    HookType::Pre => {
+                        let msg = format!(
+                            "httm aborted the {} operation, as a pre hook failed: {error}",
+                            operation.as_str()
+                        );
+                        Err(HttmError::new(&msg).into())
+                    }
+                    // the operation is already complete, so a failing post hook only warns
+                    HookType::Post => {
+                        eprintln!("WARNING: A post hook failed: {error}");
+                        Ok(())
+                    }
+                },
+            })
+    }
+
+    fn exec_hook(command: &str, event: &HookEvent) -> HttmResult<()> {
+        let json = serde_json::to_string(event)?;
+
+        let mut process_handle = ExecProcess::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        // a hook need not read its stdin, and may exit before we finish writing
+        if let Some(mut stdin) = process_handle.stdin.take() {
+            let _ = stdin.write_all(json.as_bytes());
+        }
+
+        let status = process_handle.wait()?;
+
+        if !status.success() {
+            let msg = match status.code() {
+                Some(code) => format!("\"{command}\" exited with status {code}"),
+                None => format!("\"{command}\" was terminated by a signal"),
+            };
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+}
+
+// shorthand for the hooks the user has configured
+pub fn run_hooks(
+    hook_type: HookType,
+    operation: HookOperation,
+    paths: &[PathBuf],
+    snapshot_names: &[String],
+) -> HttmResult<()> {
+    GLOBAL_CONFIG
+        .hooks
+        .run(hook_type, operation, paths, snapshot_names)
+}
+
+struct HookEvent<'a> {
+    hook_type: HookType,
+    operation: HookOperation,
+    paths: &'a [PathBuf],
+    snapshot_names: &'a [String],
+}
+
+impl<'a> Serialize for HookEvent<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("HookEvent", 6)?;
+
+        let opt_user = std::env::var("USER")
+            .or_else(|_| std::env::var("LOGNAME"))
+            .ok();
+
+        state.serialize_field("hook", self.hook_type.as_str())?;
+        state.serialize_field("operation", self.operation.as_str())?;
+        state.serialize_field("paths", self.paths)?;
+        state.serialize_field("snapshot_names", self.snapshot_names)?;
+        state.serialize_field("user", &opt_user)?;
+        state.serialize_field("uid", &nix::unistd::geteuid().as_raw())?;
+        state.end()
+    }
+}
@@ -0,0 +1,485 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::ops::Range;
+
+use ansi_term::Colour::{Cyan, Green, Red};
+
+// Myers' algorithm must keep a trace of each edit step to recover the edit script,
+// which grows quadratically with the edit distance.  Past this many edits, we give up
+// looking for the shortest script, and simply replace the remaining lines wholesale
+const MAX_EDIT_DISTANCE: usize = 2_048;
+
+// git also only checks the first 8000 bytes for a NUL byte
+const BINARY_CHECK_LEN: usize = 8_000;
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(BINARY_CHECK_LEN).any(|byte| *byte == 0u8)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffLine<'a> {
+    pub op: DiffOp,
+    pub text: &'a str,
+}
+
+#[derive(Debug, Clone)]
+pub struct LineDiff<'a> {
+    pub lines: Vec<DiffLine<'a>>,
+}
+
+impl<'a> LineDiff<'a> {
+    pub fn new(old: &'a str, new: &'a str) -> Self {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+
+        // trim any common prefix and suffix, which is cheap, before the more expensive search
+        let prefix_len = old_lines
+            .iter()
+            .zip(new_lines.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let suffix_len = old_lines[prefix_len..]
+            .iter()
+            .rev()
+            .zip(new_lines[prefix_len..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let old_middle = &old_lines[prefix_len..old_lines.len() - suffix_len];
+        let new_middle = &new_lines[prefix_len..new_lines.len() - suffix_len];
+
+        let equal = |text: &'a str| DiffLine {
+            op: DiffOp::Equal,
+            text,
+        };
+
+        let mut lines: Vec<DiffLine> = old_lines[..prefix_len].iter().copied().map(equal).collect();
+
+        match Self::myers(old_middle, new_middle) {
+            Some(middle) => lines.extend(middle),
+            None => {
+                lines.extend(old_middle.iter().map(|text| DiffLine {
+                    op: DiffOp::Delete,
+                    text,
+                }));
+                lines.extend(new_middle.iter().map(|text| DiffLine {
+                    op: DiffOp::Insert,
+                    text,
+                }));
+            }
+        }
+
+        lines.extend(
+            old_lines[old_lines.len() - suffix_len..]
+                .iter()
+                .copied()
+                .map(equal),
+        );
+
+        Self { lines }
+    }
+
+    // see: Myers, "An O(ND) Difference Algorithm and Its Variations" (1986)
+    fn myers(old: &[&'a str], new: &[&'a str]) -> Option<Vec<DiffLine<'a>>> {
+        let old_len = old.len() as isize;
+        let new_len = new.len() as isize;
+        let max = old_len + new_len;
+        let offset = max + 1;
+
+        // v is indexed by diagonal k (offset so negative k is a valid index),
+        // and holds the furthest reaching x for that diagonal
+        let mut v = vec![0isize; (2 * max + 3) as usize];
+        // each trace entry is a copy of v, before step d, for diagonals -(d + 1)..=(d + 1)
+        let mut trace: Vec<Vec<isize>> = Vec::new();
+
+        'outer: for d in 0..=max {
+            if d as usize > MAX_EDIT_DISTANCE {
+                return None;
+            }
+
+            trace.push(v[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+
+            for k in (-d..=d).step_by(2) {
+                let idx = |k: isize| (offset + k) as usize;
+
+                let mut x = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]) {
+                    v[idx(k + 1)]
+                } else {
+                    v[idx(k - 1)] + 1
+                };
+                let mut y = x - k;
+
+                while x < old_len && y < new_len && old[x as usize] == new[y as usize] {
+                    x += 1;
+                    y += 1;
+                }
+
+                v[idx(k)] = x;
+
+                if x >= old_len && y >= new_len {
+                    break 'outer;
+                }
+            }
+        }
+
+        // walk back through the trace, from the end, to recover the edit script
+        let mut res: Vec<DiffLine> = Vec::with_capacity((old_len + new_len) as usize);
+        let (mut x, mut y) = (old_len, new_len);
+
+        for (d, v) in trace.iter().enumerate().rev() {
+            let d = d as isize;
+            let idx = |k: isize| (k + d + 1) as usize;
+            let k = x - y;
+
+            let prev_k = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]) {
+                k + 1
+            } else {
+                k - 1
+            };
+
+            let prev_x = v[idx(prev_k)];
+            let prev_y = prev_x - prev_k;
+
+            while x > prev_x && y > prev_y {
+                res.push(DiffLine {
+                    op: DiffOp::Equal,
+                    text: old[(x - 1) as usize],
+                });
+                x -= 1;
+                y -= 1;
+            }
+
+            if d > 0 {
+                if x == prev_x {
+                    res.push(DiffLine {
+                        op: DiffOp::Insert,
+                        text: new[(y - 1) as usize],
+                    });
+                } else {
+                    res.push(DiffLine {
+                        op: DiffOp::Delete,
+                        text: old[(x - 1) as usize],
+                    });
+                }
+            }
+
+            x = prev_x;
+            y = prev_y;
+        }
+
+        res.reverse();
+
+        Some(res)
+    }
+
+    pub fn num_inserted(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| line.op == DiffOp::Insert)
+            .count()
+    }
+
+    pub fn num_deleted(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| line.op == DiffOp::Delete)
+            .count()
+    }
+
+    // ranges of lines containing changes, each padded with up to "context" equal lines,
+    // and merged where the padding of two neighboring changes would overlap
+    pub fn hunks(&self, context: usize) -> Vec<Range<usize>> {
+        let len = self.lines.len();
+        let mut res: Vec<Range<usize>> = Vec::new();
+        let mut idx = 0usize;
+
+        while idx < len {
+            if self.lines[idx].op == DiffOp::Equal {
+                idx += 1;
+                continue;
+            }
+
+            let start = idx.saturating_sub(context);
+            let mut end = idx;
+
+            loop {
+                while end < len && self.lines[end].op != DiffOp::Equal {
+                    end += 1;
+                }
+
+                let next_change = self.lines[end..]
+                    .iter()
+                    .position(|line| line.op != DiffOp::Equal)
+                    .map(|pos| end + pos);
+
+                match next_change {
+                    Some(next) if next - end <= context * 2 => end = next,
+                    _ => break,
+                }
+            }
+
+            let end = len.min(end + context);
+            res.push(start..end);
+            idx = end;
+        }
+
+        res
+    }
+
+    fn hunk_header(&self, hunk: &Range<usize>) -> String {
+        let count = |range: Range<usize>, op: DiffOp| {
+            self.lines[range]
+                .iter()
+                .filter(|line| line.op == DiffOp::Equal || line.op == op)
+                .count()
+        };
+
+        let old_before = count(0..hunk.start, DiffOp::Delete);
+        let old_count = count(hunk.clone(), DiffOp::Delete);
+        let new_before = count(0..hunk.start, DiffOp::Insert);
+        let new_count = count(hunk.clone(), DiffOp::Insert);
+
+        // like GNU diff, an empty range starts at the line before the hunk
+        let old_start = if old_count == 0 {
+            old_before
+        } else {
+            old_before + 1
+        };
+        let new_start = if new_count == 0 {
+            new_before
+        } else {
+            new_before + 1
+        };
+
+        format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@")
+    }
+
+    pub fn unified(&self, old_label: &str, new_label: &str, context: usize, color: bool) -> String {
+        let mut buffer = format!("--- {old_label}\n+++ {new_label}\n");
+
+        self.hunks(context).iter().for_each(|hunk| {
+            let header = self.hunk_header(hunk);
+
+            if color {
+                buffer += &Cyan.paint(header).to_string();
+            } else {
+                buffer += &header;
+            }
+            buffer.push('\n');
+
+            self.lines[hunk.clone()].iter().for_each(|line| {
+                let line_str = match line.op {
+                    DiffOp::Equal => format!(" {}", line.text),
+                    DiffOp::Delete if color => Red.paint(format!("-{}", line.text)).to_string(),
+                    DiffOp::Delete => format!("-{}", line.text),
+                    DiffOp::Insert if color => Green.paint(format!("+{}", line.text)).to_string(),
+                    DiffOp::Insert => format!("+{}", line.text),
+                };
+
+                buffer += &line_str;
+                buffer.push('\n');
+            });
+        });
+
+        buffer
+    }
+
+    // like "diff --side-by-side", deleted lines are paired with inserted lines where possible,
+    // and marked "|", otherwise deleted lines are marked "<" and inserted lines ">"
+    pub fn side_by_side(&self, width: usize, color: bool) -> String {
+        // each column is the width, less the 3 char gutter, divided by 2
+        let column_width = width.saturating_sub(3) / 2;
+
+        let mut buffer = String::new();
+        let mut idx = 0usize;
+
+        while idx < self.lines.len() {
+            if self.lines[idx].op == DiffOp::Equal {
+                let text = Self::fit_column(self.lines[idx].text, column_width);
+                buffer += &format!("{text:<column_width$}   {text}\n");
+                idx += 1;
+                continue;
+            }
+
+            let deletes: Vec<&str> = self.lines[idx..]
+                .iter()
+                .take_while(|line| line.op == DiffOp::Delete)
+                .map(|line| line.text)
+                .collect();
+            idx += deletes.len();
+
+            let inserts: Vec<&str> = self.lines[idx..]
+                .iter()
+                .take_while(|line| line.op == DiffOp::Insert)
+                .map(|line| line.text)
+                .collect();
+            idx += inserts.len();
+
+            (0..deletes.len().max(inserts.len())).for_each(|row| {
+                let left = deletes
+                    .get(row)
+                    .map(|text| Self::fit_column(text, column_width))
+                    .unwrap_or_default();
+                let right = inserts
+                    .get(row)
+                    .map(|text| Self::fit_column(text, column_width))
+                    .unwrap_or_default();
+
+                let marker = match (deletes.get(row), inserts.get(row)) {
+                    (Some(_), Some(_)) => "|",
+                    (Some(_), None) => "<",
+                    _ => ">",
+                };
+
+                let line = format!("{left:<column_width$} {marker} {right}");
+
+                let line = if !color {
+                    line
+                } else {
+                    match marker {
+                        "<" => Red.paint(line).to_string(),
+                        ">" => Green.paint(line).to_string(),
+                        _ => Cyan.paint(line).to_string(),
+                    }
+                };
+
+                buffer += line.trim_end();
+                buffer.push('\n');
+            });
+        }
+
+        buffer
+    }
+
+    // expand tabs and truncate, so each column lines up
+    fn fit_column(text: &str, column_width: usize) -> String {
+        text.replace('\t', "    ")
+            .chars()
+            .take(column_width)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ops(line_diff: &LineDiff) -> Vec<(DiffOp, String)> {
+        line_diff
+            .lines
+            .iter()
+            .map(|line| (line.op, line.text.to_owned()))
+            .collect()
+    }
+
+    // equal and deleted lines are the old text, and equal and inserted lines are the new
+    fn reconstruct(line_diff: &LineDiff, skip_op: DiffOp) -> Vec<String> {
+        line_diff
+            .lines
+            .iter()
+            .filter(|line| line.op != skip_op)
+            .map(|line| line.text.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn empty_inputs_have_no_lines() {
+        assert!(LineDiff::new("", "").lines.is_empty());
+    }
+
+    #[test]
+    fn identical_inputs_are_all_equal() {
+        let text = "a\nb\nc\n";
+        let line_diff = LineDiff::new(text, text);
+
+        assert_eq!(line_diff.lines.len(), 3);
+        assert!(line_diff.lines.iter().all(|line| line.op == DiffOp::Equal));
+    }
+
+    #[test]
+    fn insert_only() {
+        let line_diff = LineDiff::new("a\nc\n", "a\nb\nc\n");
+
+        assert_eq!(
+            ops(&line_diff),
+            vec![
+                (DiffOp::Equal, "a".to_owned()),
+                (DiffOp::Insert, "b".to_owned()),
+                (DiffOp::Equal, "c".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_into_empty() {
+        let line_diff = LineDiff::new("", "a\nb\n");
+
+        assert_eq!(
+            ops(&line_diff),
+            vec![
+                (DiffOp::Insert, "a".to_owned()),
+                (DiffOp::Insert, "b".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn mixed_edits_reconstruct_both_inputs() {
+        let old = "a\nb\nc\nd\ne\nf\n";
+        let new = "a\nx\nc\ne\nf\ny\n";
+        let line_diff = LineDiff::new(old, new);
+
+        assert_eq!(
+            reconstruct(&line_diff, DiffOp::Insert),
+            old.lines().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            reconstruct(&line_diff, DiffOp::Delete),
+            new.lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn past_max_edit_distance_replaces_wholesale() {
+        let num_lines = MAX_EDIT_DISTANCE;
+        let old: String = (0..num_lines).map(|idx| format!("old {idx}\n")).collect();
+        let new: String = (0..num_lines).map(|idx| format!("new {idx}\n")).collect();
+        let line_diff = LineDiff::new(&old, &new);
+
+        let (deleted, inserted) = line_diff.lines.split_at(num_lines);
+
+        assert!(deleted.iter().all(|line| line.op == DiffOp::Delete));
+        assert!(inserted.iter().all(|line| line.op == DiffOp::Insert));
+        assert_eq!(
+            reconstruct(&line_diff, DiffOp::Insert),
+            old.lines().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            reconstruct(&line_diff, DiffOp::Delete),
+            new.lines().collect::<Vec<_>>()
+        );
+    }
+}
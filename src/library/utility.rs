@@ -22,7 +22,7 @@ use std::{
     iter::Iterator,
     os::unix::fs::MetadataExt,
     path::{Component::RootDir, Path, PathBuf},
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 use ansi_term::Style as AnsiTermStyle;
@@ -30,7 +30,9 @@ use crossbeam::channel::{Receiver, TryRecvError};
 use lscolors::{Colorable, LsColors, Style};
 use number_prefix::NumberPrefix;
 use once_cell::sync::Lazy;
-use time::{format_description, OffsetDateTime, UtcOffset};
+use serde::{Serialize, Serializer};
+use time::format_description::well_known::Rfc3339;
+use time::{format_description, Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
 
 use crate::config::generate::PrintMode;
 use crate::data::paths::{BasicDirEntryInfo, PathData};
@@ -130,10 +132,6 @@ pub fn copy_recursive(src: &Path, dst: &Path, should_preserve: bool) -> HttmResu
     if src.is_dir() {
         create_dir_all(dst).map_err(|err| map_io_err(err, dst))?;
 
-        if should_preserve {
-            copy_attributes(src, dst)?;
-        }
-
         for entry in read_dir(src)? {
             let entry = entry?;
             let file_type = entry.file_type()?;
@@ -144,14 +142,19 @@ pub fn copy_recursive(src: &Path, dst: &Path, should_preserve: bool) -> HttmResu
                 if file_type.is_dir() {
                     copy_recursive(&entry_src, &entry_dst, should_preserve)?;
                 } else {
-                    copy(&entry_src, entry_dst).map_err(|err| map_io_err(err, dst))?;
+                    copy(&entry_src, &entry_dst).map_err(|err| map_io_err(err, dst))?;
 
                     if should_preserve {
-                        copy_attributes(src, dst)?;
+                        copy_attributes(&entry_src, &entry_dst)?;
                     }
                 }
             }
         }
+
+        // preserve dir attributes last, as copying its entries would otherwise change its timestamps
+        if should_preserve {
+            copy_attributes(src, dst)?;
+        }
     } else {
         copy(src, dst).map_err(|err| map_io_err(err, dst))?;
 
@@ -191,6 +194,22 @@ pub fn remove_recursive(src: &Path) -> HttmResult<()> {
     Ok(())
 }
 
+// the sum of the sizes of all files at or beneath a path, as a copy would write them
+pub fn get_size_recursive(src: &Path) -> u64 {
+    match src.symlink_metadata() {
+        Ok(md) if md.is_dir() => read_dir(src)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| get_size_recursive(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0),
+        Ok(md) => md.len(),
+        Err(_) => 0,
+    }
+}
+
 pub fn read_stdin() -> HttmResult<Vec<String>> {
     let stdin = std::io::stdin();
     let mut stdin = stdin.lock();
@@ -408,6 +427,12 @@ pub fn get_fs_type_from_hidden_dir(dataset_mount: &Path) -> Option<FilesystemTyp
 pub enum DateFormat {
     Display,
     Timestamp,
+    // a format description already validated, see strftime_to_format_description
+    Custom(String),
+    // ISO 8601 / RFC 3339, like "2023-01-31T13:30:00-05:00", for machine output
+    Rfc3339,
+    // like "3 hours ago", relative to now, and so independent of any UTC offset
+    Relative,
 }
 
 static DATE_FORMAT_DISPLAY: &str =
@@ -421,8 +446,21 @@ pub fn get_date(
 ) -> String {
     let date_time: OffsetDateTime = (*system_time).into();
 
-    let parsed_format = format_description::parse(get_date_format(&date_format))
-        .expect("timestamp date format is invalid");
+    let format_description = match &date_format {
+        DateFormat::Display => DATE_FORMAT_DISPLAY,
+        DateFormat::Timestamp => DATE_FORMAT_TIMESTAMP,
+        DateFormat::Custom(format_description) => format_description,
+        DateFormat::Rfc3339 => {
+            return date_time
+                .to_offset(utc_offset)
+                .format(&Rfc3339)
+                .expect("RFC 3339 date format could not be applied to the date supplied")
+        }
+        DateFormat::Relative => return get_relative_date(system_time),
+    };
+
+    let parsed_format =
+        format_description::parse(format_description).expect("timestamp date format is invalid");
 
     let raw_string = date_time
         .to_offset(utc_offset)
@@ -433,16 +471,385 @@ pub fn get_date(
         return match &date_format {
             DateFormat::Timestamp => raw_string + "_UTC",
             DateFormat::Display => raw_string + " UTC",
+            // user requested formats print exactly what the user requested
+            DateFormat::Custom(_) | DateFormat::Rfc3339 | DateFormat::Relative => raw_string,
         };
     }
 
     raw_string
 }
 
-fn get_date_format<'a>(format: &DateFormat) -> &'a str {
-    match format {
-        DateFormat::Display => DATE_FORMAT_DISPLAY,
-        DateFormat::Timestamp => DATE_FORMAT_TIMESTAMP,
+fn get_relative_date(system_time: &SystemTime) -> String {
+    const UNITS: [(u64, &str); 7] = [
+        (365 * 86_400, "year"),
+        (30 * 86_400, "month"),
+        (7 * 86_400, "week"),
+        (86_400, "day"),
+        (3_600, "hour"),
+        (60, "minute"),
+        (1, "second"),
+    ];
+
+    // a time in the future, like a clock skewed snapshot, is an error from duration_since()
+    let (secs, is_future) = match SystemTime::now().duration_since(*system_time) {
+        Ok(duration) => (duration.as_secs(), false),
+        Err(err) => (err.duration().as_secs(), true),
+    };
+
+    let (unit_secs, unit) = match UNITS.iter().find(|(unit_secs, _unit)| secs >= *unit_secs) {
+        Some(unit) => unit,
+        None => return "just now".to_owned(),
+    };
+
+    let num = secs / unit_secs;
+    let plural = if num == 1 { "" } else { "s" };
+
+    if is_future {
+        format!("in {num} {unit}{plural}")
+    } else {
+        format!("{num} {unit}{plural} ago")
+    }
+}
+
+// a date for JSON output, in the requested date format and UTC offset, but never relative,
+// as JSON is read by programs, and, in raw modes, a raw time, unless a date format was requested
+pub enum JsonDate {
+    Raw(SystemTime),
+    Formatted(String),
+}
+
+impl JsonDate {
+    pub fn new(system_time: &SystemTime) -> Self {
+        let is_raw = matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        );
+
+        let date_format = match &GLOBAL_CONFIG.requested_date_format {
+            DateFormat::Display | DateFormat::Relative if is_raw => return Self::Raw(*system_time),
+            DateFormat::Relative => DateFormat::Display,
+            date_format => date_format.clone(),
+        };
+
+        Self::Formatted(get_date(
+            GLOBAL_CONFIG.requested_utc_offset,
+            system_time,
+            date_format,
+        ))
+    }
+}
+
+impl Serialize for JsonDate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Raw(system_time) => system_time.serialize(serializer),
+            Self::Formatted(date) => date.serialize(serializer),
+        }
+    }
+}
+
+// users know strftime, so translate a strftime-like pattern, like "%Y-%m-%d",
+// into the format description syntax the time crate requires, like "[year]-[month]-[day]"
+pub fn strftime_to_format_description(strftime: &str) -> HttmResult<String> {
+    let mut format_description = String::with_capacity(strftime.len() * 4);
+    let mut chars = strftime.chars();
+
+    while let Some(current) = chars.next() {
+        match current {
+            '%' => {
+                let component = match chars.next() {
+                    Some('Y') => "[year]",
+                    Some('y') => "[year repr:last_two]",
+                    Some('m') => "[month]",
+                    Some('b' | 'h') => "[month repr:short]",
+                    Some('B') => "[month repr:long]",
+                    Some('d') => "[day]",
+                    Some('e') => "[day padding:space]",
+                    Some('j') => "[ordinal]",
+                    Some('H') => "[hour]",
+                    Some('I') => "[hour repr:12]",
+                    Some('M') => "[minute]",
+                    Some('S') => "[second]",
+                    Some('p') => "[period]",
+                    Some('a') => "[weekday repr:short]",
+                    Some('A') => "[weekday]",
+                    Some('u') => "[weekday repr:monday]",
+                    Some('w') => "[weekday repr:sunday one_indexed:false]",
+                    Some('z') => "[offset_hour sign:mandatory][offset_minute]",
+                    Some('s') => "[unix_timestamp]",
+                    Some('F') => "[year]-[month]-[day]",
+                    Some('T') => "[hour]:[minute]:[second]",
+                    Some('R') => "[hour]:[minute]",
+                    Some('D') => "[month]/[day]/[year repr:last_two]",
+                    Some('n') => "\n",
+                    Some('t') => "\t",
+                    Some('%') => "%",
+                    Some(other) => {
+                        let msg = format!(
+                            "httm does not support the date format specifier: \"%{other}\""
+                        );
+                        return Err(HttmError::new(&msg).into());
+                    }
+                    None => {
+                        return Err(HttmError::new(
+                            "A date format may not end with a lone \"%\" character.",
+                        )
+                        .into())
+                    }
+                };
+
+                format_description.push_str(component);
+            }
+            // a literal open bracket must be escaped in a format description
+            '[' => format_description.push_str("[["),
+            literal => format_description.push(literal),
+        }
+    }
+
+    // make certain the result is valid now, so get_date() never has to fail later
+    format_description::parse(&format_description).map_err(|err| {
+        HttmError::with_context("httm could not parse the requested date format", &err)
+    })?;
+
+    Ok(format_description)
+}
+
+// parses a duration like "500ms", "30s", "5m", "1h", or "7d", or a bare number of seconds
+pub fn parse_duration(raw: &str) -> HttmResult<Duration> {
+    let raw = raw.trim();
+
+    let split_idx = raw
+        .find(|character: char| !character.is_ascii_digit())
+        .unwrap_or(raw.len());
+
+    let (number, unit) = raw.split_at(split_idx);
+
+    let number: u64 = number.parse().map_err(|_err| {
+        let msg = format!("httm could not parse a number from the duration given: {raw:?}");
+        HttmError::new(&msg)
+    })?;
+
+    let opt_duration = match unit.trim() {
+        "ms" => Some(Duration::from_millis(number)),
+        "" | "s" | "sec" | "secs" => Some(Duration::from_secs(number)),
+        "m" | "min" | "mins" => number.checked_mul(60).map(Duration::from_secs),
+        "h" | "hr" | "hrs" => number.checked_mul(60 * 60).map(Duration::from_secs),
+        "d" | "day" | "days" => number.checked_mul(60 * 60 * 24).map(Duration::from_secs),
+        _ => None,
+    };
+
+    match opt_duration {
+        Some(duration) => Ok(duration),
+        None => {
+            let msg = format!(
+                "httm could not parse the duration given: {raw:?}.  Valid units are \"ms\", \"s\", \"m\", \"h\", and \"d\"."
+            );
+            Err(HttmError::new(&msg).into())
+        }
+    }
+}
+
+// parses a date like "2023-01-31", "2023-01-31 13:30", or "2023-01-31T13:30:00", in the
+// requested UTC offset, or an RFC 3339 date, like "2023-01-31T13:30:00-05:00", in its own offset
+pub fn parse_date(raw: &str, utc_offset: UtcOffset) -> HttmResult<SystemTime> {
+    const DATE_TIME_FORMATS: [&str; 4] = [
+        "[year]-[month]-[day] [hour]:[minute]:[second]",
+        "[year]-[month]-[day]T[hour]:[minute]:[second]",
+        "[year]-[month]-[day] [hour]:[minute]",
+        "[year]-[month]-[day]T[hour]:[minute]",
+    ];
+    const DATE_FORMAT: &str = "[year]-[month]-[day]";
+
+    let raw = raw.trim();
+
+    if let Ok(date_time) = OffsetDateTime::parse(raw, &Rfc3339) {
+        return Ok(date_time.into());
+    }
+
+    let opt_date_time = DATE_TIME_FORMATS
+        .iter()
+        .filter_map(|format| format_description::parse(format).ok())
+        .find_map(|format| PrimitiveDateTime::parse(raw, &format).ok())
+        .or_else(|| {
+            let format = format_description::parse(DATE_FORMAT).ok()?;
+            Date::parse(raw, &format).ok().map(Date::midnight)
+        });
+
+    match opt_date_time {
+        Some(date_time) => Ok(date_time.assume_offset(utc_offset).into()),
+        None => {
+            let msg = format!(
+                "httm could not parse the date given: {raw:?}.  Valid dates are like \"2023-01-31\", \"2023-01-31 13:30\", \"2023-01-31T13:30:00\", or \"2023-01-31T13:30:00-05:00\"."
+            );
+            Err(HttmError::new(&msg).into())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSegment<T> {
+    Literal(String),
+    Placeholder(T),
+}
+
+// splits a template, like "{mtime:%Y-%m-%d} {path}", into literals and placeholders, which
+// the caller parses, where doubled braces are literal braces, and every brace must be matched
+pub fn parse_template<T>(
+    raw: &str,
+    arg_name: &str,
+    parse_placeholder: impl Fn(&str) -> HttmResult<T>,
+) -> HttmResult<Vec<TemplateSegment<T>>> {
+    let mut segments: Vec<TemplateSegment<T>> = Vec::new();
+    let mut literal = String::new();
+    let mut chars = raw.chars().peekable();
+
+    while let Some(current) = chars.next() {
+        match current {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => {
+                            let msg = format!(
+                                "{arg_name} contains an unterminated placeholder: \"{{{placeholder}\".  Each '{{' requires a matching '}}'."
+                            );
+                            return Err(HttmError::new(&msg).into());
+                        }
+                    }
+                }
+
+                if !literal.is_empty() {
+                    segments.push(TemplateSegment::Literal(std::mem::take(&mut literal)));
+                }
+
+                segments.push(TemplateSegment::Placeholder(parse_placeholder(
+                    &placeholder,
+                )?));
+            }
+            '}' => {
+                let msg = format!(
+                    "{arg_name} contains an unmatched '}}'.  Use '}}}}' for a literal brace."
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+            _ => literal.push(current),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(TemplateSegment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+// translates a shell style glob, like "*.rs" or "src/**/main.rs", into an anchored regex,
+// where "*" and "?" never match a path separator, and "**" matches any number of path components
+pub fn glob_to_regex(glob: &str) -> HttmResult<regex::Regex> {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            '[' => {
+                let class: String = chars.clone().take_while(|c| *c != ']').collect();
+
+                // an unclosed bracket is just a bracket
+                if class.is_empty() || chars.clone().nth(class.chars().count()).is_none() {
+                    pattern.push_str(r"\[");
+                    continue;
+                }
+
+                chars.nth(class.chars().count());
+
+                pattern.push('[');
+
+                // like '*' and '?', a negated class never matches a path separator
+                let class = match class.strip_prefix('!') {
+                    Some(negated) => {
+                        pattern.push_str("^/");
+                        negated
+                    }
+                    None => class.as_str(),
+                };
+
+                class.chars().for_each(|c| match c {
+                    '\\' | '[' | '&' | '~' => {
+                        pattern.push('\\');
+                        pattern.push(c);
+                    }
+                    _ => pattern.push(c),
+                });
+
+                pattern.push(']');
+            }
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    pattern.push('$');
+
+    regex::Regex::new(&pattern).map_err(|err| {
+        let msg = format!("httm could not parse the glob given: {glob:?}");
+        HttmError::with_context(&msg, &err).into()
+    })
+}
+
+// parses a size like "4096", "512K", "1.5M", or "2GiB", in which units are powers of 1024
+pub fn parse_size(raw: &str) -> HttmResult<u64> {
+    const UNITS: [&str; 6] = ["", "K", "M", "G", "T", "P"];
+
+    let raw = raw.trim();
+
+    let split_idx = raw
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(raw.len());
+    let (number, unit) = raw.split_at(split_idx);
+
+    let unit = unit.trim().to_ascii_uppercase();
+    let unit = unit
+        .strip_suffix("IB")
+        .or_else(|| unit.strip_suffix('B'))
+        .unwrap_or(&unit);
+
+    let opt_exponent = UNITS.iter().position(|known| *known == unit);
+
+    match (number.parse::<f64>(), opt_exponent) {
+        (Ok(number), Some(exponent)) if number.is_finite() => {
+            Ok((number * 1024f64.powi(exponent as i32)).round() as u64)
+        }
+        _ => {
+            let msg = format!(
+                "httm could not parse the size given: {raw:?}.  Valid sizes are like \"4096\", \"512K\", \"1.5M\", or \"2GiB\"."
+            );
+            Err(HttmError::new(&msg).into())
+        }
     }
 }
 
@@ -455,6 +862,14 @@ pub fn display_human_size(size: u64) -> String {
     }
 }
 
+pub fn display_human_size_delta(old_size: u64, new_size: u64) -> String {
+    if new_size >= old_size {
+        format!("+{}", display_human_size(new_size - old_size))
+    } else {
+        format!("-{}", display_human_size(old_size - new_size))
+    }
+}
+
 pub fn compare_modify_time<T>(src: T, dst: T) -> HttmResult<()>
 where
     T: CompareModifyTime,
@@ -497,3 +912,179 @@ impl CompareModifyTime for PathData {
         self.path_buf.as_path()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch_secs(raw: &str, utc_offset: UtcOffset) -> u64 {
+        parse_date(raw, utc_offset)
+            .unwrap()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn date_in_requested_offset() {
+        let eastern = UtcOffset::from_hms(-5, 0, 0).unwrap();
+
+        assert_eq!(epoch_secs("2023-01-31", UtcOffset::UTC), 1_675_123_200);
+        assert_eq!(
+            epoch_secs("2023-01-31 13:30", UtcOffset::UTC),
+            1_675_171_800
+        );
+        assert_eq!(
+            epoch_secs("2023-01-31T13:30:15", UtcOffset::UTC),
+            1_675_171_815
+        );
+        assert_eq!(epoch_secs(" 2023-01-31 13:30 ", eastern), 1_675_189_800);
+    }
+
+    #[test]
+    fn rfc3339_date_in_its_own_offset() {
+        let eastern = UtcOffset::from_hms(-5, 0, 0).unwrap();
+
+        assert_eq!(
+            epoch_secs("2023-01-31T13:30:00-05:00", UtcOffset::UTC),
+            epoch_secs("2023-01-31 13:30", eastern)
+        );
+        assert_eq!(
+            epoch_secs("2023-01-31T18:30:00Z", eastern),
+            epoch_secs("2023-01-31 18:30", UtcOffset::UTC)
+        );
+    }
+
+    #[test]
+    fn date_rejects_invalid() {
+        [
+            "",
+            "yesterday",
+            "2023/01/31",
+            "2023-02-30",
+            "2023-01-31 25:00",
+        ]
+        .iter()
+        .for_each(|raw| assert!(parse_date(raw, UtcOffset::UTC).is_err(), "{raw}"));
+    }
+
+    #[test]
+    fn duration_units() {
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration(" 5 min ").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3_600));
+        assert_eq!(
+            parse_duration("7days").unwrap(),
+            Duration::from_secs(604_800)
+        );
+    }
+
+    #[test]
+    fn duration_rejects_invalid() {
+        ["", "s", "-5s", "1.5h", "5w", "10 fortnights"]
+            .iter()
+            .for_each(|raw| assert!(parse_duration(raw).is_err(), "{raw}"));
+    }
+
+    #[test]
+    fn duration_rejects_overflow() {
+        let max = u64::MAX;
+
+        assert_eq!(
+            parse_duration(&format!("{max}s")).unwrap(),
+            Duration::from_secs(max)
+        );
+        assert!(parse_duration(&format!("{max}m")).is_err());
+        assert!(parse_duration(&format!("{}h", max / 60)).is_err());
+        assert!(parse_duration(&format!("{}d", max / 3_600)).is_err());
+    }
+
+    #[test]
+    fn size_units() {
+        assert_eq!(parse_size("4096").unwrap(), 4_096);
+        assert_eq!(parse_size("512K").unwrap(), 524_288);
+        assert_eq!(parse_size("1.5M").unwrap(), 1_572_864);
+        assert_eq!(parse_size("2GiB").unwrap(), 2_147_483_648);
+        assert_eq!(parse_size(" 1 tb ").unwrap(), 1_099_511_627_776);
+    }
+
+    #[test]
+    fn size_rejects_invalid() {
+        ["", "K", "12X", "1.2.3M", "-1K"]
+            .iter()
+            .for_each(|raw| assert!(parse_size(raw).is_err(), "{raw}"));
+    }
+
+    #[test]
+    fn template_splits_literals_and_placeholders() {
+        let segments = parse_template("{{{a}}} {b}", "TEMPLATE", |placeholder| {
+            Ok(placeholder.to_owned())
+        })
+        .unwrap();
+
+        assert_eq!(
+            segments,
+            vec![
+                TemplateSegment::Literal("{".to_owned()),
+                TemplateSegment::Placeholder("a".to_owned()),
+                TemplateSegment::Literal("} ".to_owned()),
+                TemplateSegment::Placeholder("b".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn template_rejects_unmatched_braces() {
+        let parse = |raw: &str| parse_template(raw, "TEMPLATE", |_placeholder| Ok(()));
+
+        assert!(parse("{path").is_err());
+        assert!(parse("{size} {path").is_err());
+        assert!(parse("path}").is_err());
+    }
+
+    #[test]
+    fn glob_star_stays_within_a_component() {
+        let regex = glob_to_regex("*.txt").unwrap();
+
+        assert!(regex.is_match("notes.txt"));
+        assert!(!regex.is_match("dir/notes.txt"));
+        assert!(!regex.is_match("notes.txt.bak"));
+    }
+
+    #[test]
+    fn glob_double_star_slash_matches_any_depth() {
+        let regex = glob_to_regex("**/notes.txt").unwrap();
+
+        assert!(regex.is_match("notes.txt"));
+        assert!(regex.is_match("dir/notes.txt"));
+        assert!(regex.is_match("dir/sub/notes.txt"));
+        assert!(!regex.is_match("dir/other.txt"));
+    }
+
+    #[test]
+    fn glob_negated_class() {
+        let regex = glob_to_regex("file[!0-9]").unwrap();
+
+        assert!(regex.is_match("filea"));
+        assert!(!regex.is_match("file1"));
+        assert!(!regex.is_match("file/"));
+    }
+
+    #[test]
+    fn glob_unclosed_bracket_is_literal() {
+        let regex = glob_to_regex("file[ab").unwrap();
+
+        assert!(regex.is_match("file[ab"));
+        assert!(!regex.is_match("filea"));
+    }
+
+    #[test]
+    fn glob_escapes_regex_metacharacters() {
+        let regex = glob_to_regex("a.b+c").unwrap();
+
+        assert!(regex.is_match("a.b+c"));
+        assert!(!regex.is_match("aXbbc"));
+    }
+}
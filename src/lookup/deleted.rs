@@ -19,13 +19,19 @@ use std::{
     ffi::OsString,
     fs::read_dir,
     ops::Deref,
+    os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
 };
 
 use hashbrown::{HashMap, HashSet};
+use once_cell::sync::OnceCell;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 
-use crate::data::paths::{BasicDirEntryInfo, PathData};
+use crate::config::generate::PrintMode;
+use crate::data::paths::{BasicDirEntryInfo, CompareVersionsContainer, PathData};
 use crate::library::results::HttmResult;
+use crate::library::utility::get_delimiter;
 use crate::lookup::versions::RelativePathAndSnapMounts;
 use crate::GLOBAL_CONFIG;
 
@@ -34,11 +40,16 @@ use super::versions::VersionsMap;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DeletedFilesBundle {
     inner: Vec<BasicDirEntryInfo>,
+    // entries which only appear deleted, because they were renamed, see DETECT_RENAMES
+    renamed: Vec<RenamedEntry>,
 }
 
 impl From<Vec<BasicDirEntryInfo>> for DeletedFilesBundle {
     fn from(vec: Vec<BasicDirEntryInfo>) -> Self {
-        Self { inner: vec }
+        Self {
+            inner: vec,
+            renamed: Vec::new(),
+        }
     }
 }
 
@@ -51,8 +62,8 @@ impl Deref for DeletedFilesBundle {
 }
 
 impl DeletedFilesBundle {
-    pub fn into_inner(self) -> Vec<BasicDirEntryInfo> {
-        self.inner
+    pub fn into_inner_and_renamed(self) -> (Vec<BasicDirEntryInfo>, Vec<RenamedEntry>) {
+        (self.inner, self.renamed)
     }
 }
 
@@ -87,9 +98,31 @@ impl DeletedFilesBundle {
                 .map(|basic_info| (basic_info.get_filename().to_os_string(), basic_info))
                 .collect();
 
-        let inner = basic_info_map.into_values().collect();
+        if !GLOBAL_CONFIG.opt_detect_renames {
+            let inner = basic_info_map.into_values().collect();
+
+            return DeletedFilesBundle {
+                inner,
+                renamed: Vec::new(),
+            };
+        }
+
+        let live_index = LiveEntriesIndex::new(&requested_dir_pathdata.path_buf);
+
+        let mut inner: Vec<BasicDirEntryInfo> = Vec::new();
+        let mut renamed: Vec<RenamedEntry> = Vec::new();
 
-        DeletedFilesBundle { inner }
+        basic_info_map.into_values().for_each(|basic_info| {
+            match live_index.find_renamed(&basic_info) {
+                Some(live_path) => renamed.push(RenamedEntry {
+                    snap_entry: basic_info,
+                    live_path,
+                }),
+                None => inner.push(basic_info),
+            }
+        });
+
+        DeletedFilesBundle { inner, renamed }
     }
 
     fn get_unique_deleted_for_dir(
@@ -138,6 +171,207 @@ impl DeletedFilesBundle {
     }
 }
 
+// a snapshot entry, which is not deleted, but which lives on under a new name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenamedEntry {
+    pub snap_entry: BasicDirEntryInfo,
+    pub live_path: PathBuf,
+}
+
+// the live entries of a directory, which a deleted entry may have been renamed to
+struct LiveEntriesIndex {
+    // key: inode number
+    by_inode: HashMap<u64, PathBuf>,
+    // key: file size, regular files only
+    by_size: HashMap<u64, Vec<PathBuf>>,
+}
+
+impl LiveEntriesIndex {
+    fn new(requested_dir: &Path) -> Self {
+        let mut by_inode: HashMap<u64, PathBuf> = HashMap::new();
+        let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+
+        read_dir(requested_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|dir_entry| {
+                let path = dir_entry.path();
+                path.symlink_metadata().ok().map(|md| (path, md))
+            })
+            .for_each(|(path, md)| {
+                if md.is_file() && md.len() > 0 {
+                    by_size.entry(md.len()).or_default().push(path.clone());
+                }
+
+                by_inode.insert(md.ino(), path);
+            });
+
+        Self { by_inode, by_size }
+    }
+
+    // a deleted entry was renamed if a live entry, under a name the snapshot doesn't contain,
+    // has the same inode number, or, for regular files, the same contents
+    fn find_renamed(&self, deleted: &BasicDirEntryInfo) -> Option<PathBuf> {
+        let snap_md = deleted.path.symlink_metadata().ok()?;
+        let snap_dir = deleted.path.parent()?;
+
+        let is_new_name = |live_path: &&PathBuf| -> bool {
+            live_path
+                .file_name()
+                .is_some_and(|file_name| snap_dir.join(file_name).symlink_metadata().is_err())
+        };
+
+        // inode numbers are only meaningful when the snapshot and the live entry are on the same
+        // filesystem, which isn't necessarily so, when a snapshot dir is aliased to a local dir
+        if GLOBAL_CONFIG
+            .dataset_collection
+            .opt_map_of_aliases
+            .is_none()
+        {
+            let opt_same_inode = self
+                .by_inode
+                .get(&snap_md.ino())
+                .filter(is_new_name)
+                .filter(|live_path| {
+                    live_path.symlink_metadata().is_ok_and(|live_md| {
+                        // where a filesystem records birth times, an inode number
+                        // reused by a new file will have a different birth time
+                        let is_same_birth = match (live_md.created(), snap_md.created()) {
+                            (Ok(live_birth), Ok(snap_birth)) => live_birth == snap_birth,
+                            _ => true,
+                        };
+
+                        live_md.file_type() == snap_md.file_type() && is_same_birth
+                    })
+                });
+
+            if let Some(live_path) = opt_same_inode {
+                return Some(live_path.clone());
+            }
+        }
+
+        if !snap_md.is_file() {
+            return None;
+        }
+
+        let snap_hash: OnceCell<Option<u32>> = OnceCell::new();
+
+        self.by_size
+            .get(&snap_md.len())?
+            .iter()
+            .filter(is_new_name)
+            .find(|live_path| {
+                let opt_snap_hash = snap_hash
+                    .get_or_init(|| CompareVersionsContainer::get_path_hash(&deleted.path).ok());
+
+                // a matching hash may be a collision, which would hide a file truly deleted,
+                // so only a byte for byte comparison confirms a rename
+                match opt_snap_hash {
+                    Some(hash) => {
+                        CompareVersionsContainer::get_path_hash(live_path)
+                            .is_ok_and(|live_hash| live_hash == *hash)
+                            && CompareVersionsContainer::is_same_contents(&deleted.path, live_path)
+                                .unwrap_or(false)
+                    }
+                    None => false,
+                }
+            })
+            .cloned()
+    }
+}
+
+// renamed entries, as displayed in non-interactive deleted modes
+pub struct RenamedMap {
+    inner: Vec<(PathBuf, PathBuf)>,
+}
+
+impl RenamedMap {
+    pub fn new(renamed: &[RenamedEntry], pseudo_live_dir: &Path) -> Self {
+        let inner = renamed
+            .iter()
+            .map(|entry| {
+                (
+                    pseudo_live_dir.join(entry.snap_entry.get_filename()),
+                    entry.live_path.clone(),
+                )
+            })
+            .collect();
+
+        Self { inner }
+    }
+}
+
+impl std::fmt::Display for RenamedMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = get_delimiter();
+
+        if GLOBAL_CONFIG.opt_json {
+            let res = match GLOBAL_CONFIG.print_mode {
+                PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                    serde_json::to_string(self)
+                }
+                PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+            };
+
+            return match res {
+                Ok(json) => write!(f, "{json}{delimiter}"),
+                Err(_) => Err(std::fmt::Error),
+            };
+        }
+
+        self.inner
+            .iter()
+            .try_for_each(|(old_path, new_path)| match GLOBAL_CONFIG.print_mode {
+                PrintMode::RawNewline | PrintMode::RawZero => write!(
+                    f,
+                    "{}\t{}{delimiter}",
+                    old_path.to_string_lossy(),
+                    new_path.to_string_lossy()
+                ),
+                PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
+                    writeln!(f, "{old_path:?} : renamed to {new_path:?}")
+                }
+            })
+    }
+}
+
+impl Serialize for RenamedMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct RenamedPair<'a> {
+            old_path: &'a Path,
+            new_path: &'a Path,
+        }
+
+        impl<'a> Serialize for RenamedPair<'a> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state = serializer.serialize_struct("RenamedPair", 2)?;
+
+                state.serialize_field("path", self.old_path)?;
+                state.serialize_field("renamed_to", self.new_path)?;
+                state.end()
+            }
+        }
+
+        let pairs: Vec<RenamedPair> = self
+            .inner
+            .iter()
+            .map(|(old_path, new_path)| RenamedPair { old_path, new_path })
+            .collect();
+
+        let mut state = serializer.serialize_struct("RenamedMap", 1)?;
+
+        state.serialize_field("renamed", &pairs)?;
+        state.end()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LastInTimeSet {
     inner: Vec<PathBuf>,
@@ -0,0 +1,293 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::path::Path;
+use std::process::Command as ExecProcess;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::config::generate::PrintMode;
+use crate::data::paths::{BasicDirEntryInfo, PathData};
+use crate::exec::snapshot::TakeSnapshot;
+use crate::library::utility::{get_date, get_delimiter, JsonDate};
+use crate::lookup::snap_names::DeconstructedSnapPathData;
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+// a snapshot and the time it was taken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapInstant {
+    pub snap_name: String,
+    pub time: SystemTime,
+}
+
+impl SnapInstant {
+    fn new(snap_mount: &Path) -> Option<Self> {
+        let snap_name = DeconstructedSnapPathData::from_snap_mount(&PathData::from(snap_mount))
+            .map(|deconstructed| deconstructed.snap_name)
+            .unwrap_or_else(|| snap_mount.to_string_lossy().into_owned());
+
+        // ZFS knows when a snapshot was created, for other filesystems,
+        // the mtime of the snap mount is the best approximation we have
+        let time = match SNAP_CREATION_TIMES.get(&snap_name) {
+            Some(time) => *time,
+            None => snap_mount.symlink_metadata().ok()?.modified().ok()?,
+        };
+
+        Some(Self { snap_name, time })
+    }
+
+    // the snapshot which contains a snapshot version, like "/pool/.zfs/snapshot/snap_1/file"
+    pub fn from_version(version: &PathData) -> Option<Self> {
+        let relpath = DeconstructedSnapPathData::from_snap_mount(version)?.relpath?;
+
+        let snap_mount = version
+            .path_buf
+            .ancestors()
+            .nth(relpath.components().count())?;
+
+        Self::new(snap_mount)
+    }
+
+    pub fn get_date(&self) -> String {
+        get_date(
+            GLOBAL_CONFIG.requested_utc_offset,
+            &self.time,
+            GLOBAL_CONFIG.requested_date_format.clone(),
+        )
+    }
+}
+
+impl Serialize for SnapInstant {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("SnapInstant", 2)?;
+
+        state.serialize_field("snapshot", &self.snap_name)?;
+        state.serialize_field("time", &JsonDate::new(&self.time))?;
+        state.end()
+    }
+}
+
+// key: full ZFS snapshot name, like "pool/dataset@snap_name", val: its creation time
+static SNAP_CREATION_TIMES: Lazy<HashMap<String, SystemTime>> = Lazy::new(|| {
+    let process_output = match TakeSnapshot::get_zfs_command().map(|zfs_command| {
+        ExecProcess::new(zfs_command)
+            .args(["list", "-Hp", "-t", "snapshot", "-o", "name,creation"])
+            .output()
+    }) {
+        Ok(Ok(process_output)) => process_output,
+        _ => return HashMap::new(),
+    };
+
+    String::from_utf8_lossy(&process_output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (name, creation) = line.split_once('\t')?;
+            let secs = creation.trim().parse::<u64>().ok()?;
+
+            Some((name.to_owned(), UNIX_EPOCH + Duration::from_secs(secs)))
+        })
+        .collect()
+});
+
+// the window in which a deleted file must have been deleted: after the last snapshot
+// which contains the file, and before the first snapshot taken thereafter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletionWindow {
+    pub last_seen: SnapInstant,
+    // None when no snapshot has been taken since the file was last seen
+    pub opt_first_missing: Option<SnapInstant>,
+}
+
+impl std::fmt::Display for DeletionWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.opt_first_missing {
+            Some(first_missing) => write!(
+                f,
+                "deleted between {} and {}",
+                self.last_seen.get_date(),
+                first_missing.get_date()
+            ),
+            None => write!(f, "deleted after {}", self.last_seen.get_date()),
+        }
+    }
+}
+
+impl DeletionWindow {
+    pub fn new(pseudo_live_path: &Path) -> Option<Self> {
+        let pathdata = PathData::from(pseudo_live_path);
+
+        let requested_snap_datasets = GLOBAL_CONFIG
+            .dataset_collection
+            .snaps_selected_for_search
+            .get_value();
+
+        // every snapshot which might contain the file, and whether it does
+        let mut snaps_searched: Vec<(SnapInstant, bool)> =
+            VersionsMap::get_search_bundles(&pathdata, requested_snap_datasets)
+                .flat_map(|search_bundle| {
+                    search_bundle
+                        .snap_mounts
+                        .iter()
+                        .filter_map(move |snap_mount| {
+                            let is_present = snap_mount
+                                .join(search_bundle.relative_path)
+                                .symlink_metadata()
+                                .is_ok();
+
+                            SnapInstant::new(snap_mount).map(|instant| (instant, is_present))
+                        })
+                })
+                .collect();
+
+        snaps_searched.sort_by_key(|(instant, _)| instant.time);
+
+        let last_seen_idx = snaps_searched
+            .iter()
+            .rposition(|(_, is_present)| *is_present)?;
+
+        let mut snaps_after = snaps_searched.into_iter().skip(last_seen_idx);
+
+        let last_seen = snaps_after.next()?.0;
+        let opt_first_missing = snaps_after.next().map(|(instant, _)| instant);
+
+        Some(Self {
+            last_seen,
+            opt_first_missing,
+        })
+    }
+}
+
+pub struct DeletionWindowMap {
+    inner: Vec<(PathData, DeletionWindow)>,
+}
+
+impl DeletionWindowMap {
+    pub fn new(entries: &[BasicDirEntryInfo]) -> Self {
+        let inner = entries
+            .iter()
+            .filter_map(|basic_info| {
+                DeletionWindow::new(&basic_info.path)
+                    .map(|window| (PathData::from(basic_info), window))
+            })
+            .collect();
+
+        Self { inner }
+    }
+}
+
+impl std::fmt::Display for DeletionWindowMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delimiter = get_delimiter();
+
+        if GLOBAL_CONFIG.opt_json {
+            let res = match GLOBAL_CONFIG.print_mode {
+                PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                    serde_json::to_string(self)
+                }
+                PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+            };
+
+            return match res {
+                Ok(json) => write!(f, "{json}{delimiter}"),
+                Err(_) => Err(std::fmt::Error),
+            };
+        }
+
+        self.inner.iter().try_for_each(|(pathdata, window)| {
+            let path = pathdata.path_buf.to_string_lossy();
+
+            match GLOBAL_CONFIG.print_mode {
+                PrintMode::RawNewline | PrintMode::RawZero => {
+                    let to_secs = |instant: &SnapInstant| {
+                        instant
+                            .time
+                            .duration_since(UNIX_EPOCH)
+                            .map(|duration| duration.as_secs().to_string())
+                            .unwrap_or_default()
+                    };
+
+                    let first_missing = window
+                        .opt_first_missing
+                        .as_ref()
+                        .map(to_secs)
+                        .unwrap_or_default();
+
+                    write!(
+                        f,
+                        "{path}\t{}\t{first_missing}{delimiter}",
+                        to_secs(&window.last_seen)
+                    )
+                }
+                PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
+                    writeln!(f, "\"{path}\" : {window}")?;
+                    writeln!(f, "\tlast seen in:     {}", window.last_seen.snap_name)?;
+
+                    match &window.opt_first_missing {
+                        Some(first_missing) => {
+                            writeln!(f, "\tfirst missing in: {}", first_missing.snap_name)
+                        }
+                        None => writeln!(f, "\tfirst missing in: (no snapshot taken since)"),
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl Serialize for DeletionWindowMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct DeletedEntry<'a> {
+            pathdata: &'a PathData,
+            window: &'a DeletionWindow,
+        }
+
+        impl<'a> Serialize for DeletedEntry<'a> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state = serializer.serialize_struct("DeletedEntry", 3)?;
+
+                state.serialize_field("path", &self.pathdata.path_buf)?;
+                state.serialize_field("last_seen", &self.window.last_seen)?;
+                state.serialize_field("first_missing", &self.window.opt_first_missing)?;
+                state.end()
+            }
+        }
+
+        let entries: Vec<DeletedEntry> = self
+            .inner
+            .iter()
+            .map(|(pathdata, window)| DeletedEntry { pathdata, window })
+            .collect();
+
+        let mut state = serializer.serialize_struct("DeletionWindowMap", 1)?;
+
+        state.serialize_field("deleted", &entries)?;
+        state.end()
+    }
+}
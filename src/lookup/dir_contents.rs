@@ -0,0 +1,170 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::fs::read_dir;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use hashbrown::HashMap;
+
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, ZFS_HIDDEN_DIRECTORY};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntrySummary {
+    pub size: u64,
+    pub modify_time: SystemTime,
+    pub is_dir: bool,
+    pub inode: u64,
+    pub opt_birth_time: Option<SystemTime>,
+}
+
+impl EntrySummary {
+    // a directory's size and modify time only change when its entries change,
+    // which are counted on their own, so only files are ever modified
+    pub fn is_modified(&self, previous: &Self) -> bool {
+        previous.is_dir != self.is_dir
+            || (!self.is_dir
+                && (previous.size != self.size || previous.modify_time != self.modify_time))
+    }
+
+    // whether an entry is the same filesystem object as another entry, only
+    // meaningful where the filesystem preserves inode numbers in snapshots, like ZFS
+    pub fn is_same_inode(&self, other: &Self) -> bool {
+        // where a filesystem records birth times, an inode number
+        // reused by a new file will have a different birth time
+        let is_same_birth = match (self.opt_birth_time, other.opt_birth_time) {
+            (Some(self_birth), Some(other_birth)) => self_birth == other_birth,
+            _ => true,
+        };
+
+        self.inode == other.inode && self.is_dir == other.is_dir && is_same_birth
+    }
+}
+
+// every entry beneath a directory version, key: the entry's path relative to the directory
+pub struct DirContents {
+    pub inner: HashMap<PathBuf, EntrySummary>,
+}
+
+impl DirContents {
+    pub fn new(dir: &Path) -> Self {
+        let mut inner = HashMap::new();
+
+        // don't cross into other filesystems, like nested datasets, which
+        // would be empty directories on a snapshot, but not on a live dir
+        if let Ok(md) = dir.symlink_metadata() {
+            Self::walk(dir, dir, md.dev(), &mut inner);
+        }
+
+        Self { inner }
+    }
+
+    fn walk(base: &Path, dir: &Path, dev: u64, inner: &mut HashMap<PathBuf, EntrySummary>) {
+        // no errors will be propagated here, as it is
+        // far too likely to run into a dir we don't have permissions to view
+        read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|dir_entry| {
+                let file_name = dir_entry.file_name();
+                file_name != ZFS_HIDDEN_DIRECTORY && file_name != BTRFS_SNAPPER_HIDDEN_DIRECTORY
+            })
+            .for_each(|dir_entry| {
+                // DirEntry metadata never traverses symlinks
+                let md = match dir_entry.metadata() {
+                    Ok(md) => md,
+                    Err(_) => return,
+                };
+
+                let path = dir_entry.path();
+
+                if md.is_dir() && md.dev() == dev {
+                    Self::walk(base, &path, dev, inner);
+                }
+
+                if let Ok(relative_path) = path.strip_prefix(base) {
+                    inner.insert(
+                        relative_path.to_path_buf(),
+                        EntrySummary {
+                            size: md.len(),
+                            modify_time: md.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                            is_dir: md.is_dir(),
+                            inode: md.ino(),
+                            opt_birth_time: md.created().ok(),
+                        },
+                    );
+                }
+            });
+    }
+
+    pub fn get_num_files(&self) -> usize {
+        self.inner.values().filter(|entry| !entry.is_dir).count()
+    }
+
+    pub fn get_total_size(&self) -> u64 {
+        self.inner
+            .values()
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| entry.size)
+            .sum()
+    }
+
+    pub fn compare(&self, previous: &Self) -> EntryChanges {
+        let added = self
+            .inner
+            .keys()
+            .filter(|relative_path| !previous.inner.contains_key(*relative_path))
+            .count();
+
+        let removed = previous
+            .inner
+            .keys()
+            .filter(|relative_path| !self.inner.contains_key(*relative_path))
+            .count();
+
+        let changed = self
+            .inner
+            .iter()
+            .filter(|(relative_path, entry)| {
+                previous
+                    .inner
+                    .get(*relative_path)
+                    .is_some_and(|previous_entry| entry.is_modified(previous_entry))
+            })
+            .count();
+
+        EntryChanges {
+            added,
+            removed,
+            changed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryChanges {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+impl EntryChanges {
+    pub fn is_unchanged(&self) -> bool {
+        self.added == 0 && self.removed == 0 && self.changed == 0
+    }
+}
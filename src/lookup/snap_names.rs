@@ -16,12 +16,16 @@
 // that was distributed with this source code.
 
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use std::{collections::BTreeMap, ops::Deref};
 
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
 
 use crate::config::generate::ListSnapsFilters;
 use crate::data::paths::PathData;
+use crate::lookup::deletion_window::SnapInstant;
 use crate::parse::aliases::FilesystemType;
 use crate::GLOBAL_CONFIG;
 
@@ -47,7 +51,7 @@ impl Deref for SnapNameMap {
 }
 
 impl SnapNameMap {
-    pub fn exec(versions_map: VersionsMap, opt_filters: &Option<ListSnapsFilters>) -> Self {
+    pub fn exec(versions_map: &VersionsMap, opt_filters: &Option<ListSnapsFilters>) -> Self {
         let snap_name_map = Self::get_snap_names(versions_map, opt_filters);
 
         snap_name_map.deref().iter().for_each(|(pathdata, snaps)| {
@@ -64,18 +68,17 @@ impl SnapNameMap {
     }
 
     fn get_snap_names(
-        version_map: VersionsMap,
+        version_map: &VersionsMap,
         opt_filters: &Option<ListSnapsFilters>,
     ) -> SnapNameMap {
         let inner: BTreeMap<PathData, Vec<String>> = version_map
-            .inner
-            .into_iter()
+            .iter()
             .map(|(pathdata, vec_snaps)| {
                 // use par iter here because no one else is using the global rayon threadpool any more
                 let snap_names: Vec<String> = vec_snaps
-                    .into_par_iter()
+                    .par_iter()
                     .filter_map(|pathdata| {
-                        DeconstructedSnapPathData::new(&pathdata, false)
+                        DeconstructedSnapPathData::new(pathdata, false)
                             .map(|deconstructed| deconstructed.snap_name)
                     })
                     .filter(|snap| {
@@ -91,7 +94,7 @@ impl SnapNameMap {
                     })
                     .collect();
 
-                (pathdata, snap_names)
+                (pathdata.clone(), snap_names)
             })
             .collect();
 
@@ -118,12 +121,37 @@ impl SnapNameMap {
     }
 }
 
+// the snapshot on which a snapshot version resides, so users need not confuse
+// a version's modify time with the time its snapshot was taken
+pub struct VersionSnapInfo {
+    pub snap_name: String,
+    pub dataset: String,
+    pub opt_snap_time: Option<SystemTime>,
+}
+
+impl VersionSnapInfo {
+    pub fn new(snap_version: &PathData) -> Option<Self> {
+        let deconstructed = DeconstructedSnapPathData::from_snap_mount(snap_version)?;
+        let opt_snap_time =
+            SnapInstant::from_version(snap_version).map(|snap_instant| snap_instant.time);
+
+        Some(Self {
+            snap_name: deconstructed.snap_name,
+            dataset: deconstructed.dataset,
+            opt_snap_time,
+        })
+    }
+}
+
 // allow dead code here because this could be useful re: finding ZFS objects
 // zdb uses snap name and relative path for instance
 #[allow(dead_code)]
 pub struct DeconstructedSnapPathData {
-    snap_name: String,
-    relpath: Option<PathBuf>,
+    pub snap_name: String,
+    pub dataset: String,
+    pub fs_type: FilesystemType,
+    pub relpath: Option<PathBuf>,
+    pub dataset_mount: PathBuf,
 }
 
 impl DeconstructedSnapPathData {
@@ -147,11 +175,14 @@ impl DeconstructedSnapPathData {
             Some(md) if md.fs_type == FilesystemType::Zfs => {
                 opt_split.map(|(snap, relpath)| DeconstructedSnapPathData {
                     snap_name: format!("{}@{snap}", md.source),
+                    dataset: md.source.clone(),
+                    fs_type: FilesystemType::Zfs,
                     relpath: if include_relative_path {
                         Some(PathBuf::from(relpath))
                     } else {
                         None
                     },
+                    dataset_mount: dataset_path.to_path_buf(),
                 })
             }
             Some(_md) => {
@@ -161,4 +192,89 @@ impl DeconstructedSnapPathData {
             _ => None,
         }
     }
+
+    // unlike new(), which is only concerned with ZFS snapshot names, here we
+    // work backwards from any snap mount, so btrfs and nilfs2 versions also have
+    // a name and dataset, and we don't warn, because display modes call this for every version
+    pub fn from_snap_mount(pathdata: &PathData) -> Option<Self> {
+        let (snap_mount, dataset_mount) = pathdata.path_buf.ancestors().find_map(|ancestor| {
+            SNAP_MOUNT_INDEX
+                .get(ancestor)
+                .map(|dataset_mount| (ancestor, dataset_mount))
+        })?;
+
+        let dataset_md = GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_datasets
+            .get(dataset_mount)?;
+
+        let snap_name = match dataset_md.fs_type {
+            FilesystemType::Zfs => format!(
+                "{}@{}",
+                dataset_md.source,
+                snap_mount.file_name()?.to_string_lossy()
+            ),
+            // btrfs and nilfs2 snapshots have no canonical name, so the snap mount,
+            // relative to the dataset mount, where possible, is the best we can do
+            FilesystemType::Btrfs | FilesystemType::Nilfs2 => snap_mount
+                .strip_prefix(dataset_mount)
+                .unwrap_or(snap_mount)
+                .to_string_lossy()
+                .into_owned(),
+        };
+
+        let relpath = pathdata
+            .path_buf
+            .strip_prefix(snap_mount)
+            .ok()
+            .map(Path::to_path_buf);
+
+        Some(DeconstructedSnapPathData {
+            snap_name,
+            dataset: dataset_md.source.clone(),
+            fs_type: dataset_md.fs_type.clone(),
+            relpath,
+            dataset_mount: dataset_mount.clone(),
+        })
+    }
+
+    // the live version is the relative path joined to the dataset mount, or,
+    // if the dataset mount is the remote dir of an alias, joined to the local dir
+    pub fn get_live_path(&self) -> Option<PathBuf> {
+        let relpath = self.relpath.as_ref()?;
+
+        let opt_aliased_local_dir = GLOBAL_CONFIG
+            .dataset_collection
+            .opt_map_of_aliases
+            .as_ref()
+            .and_then(|map_of_aliases| {
+                map_of_aliases.iter().find_map(|(local_dir, alias_info)| {
+                    if alias_info.remote_dir == self.dataset_mount {
+                        Some(local_dir)
+                    } else {
+                        None
+                    }
+                })
+            });
+
+        match opt_aliased_local_dir {
+            Some(local_dir) => Some(local_dir.join(relpath)),
+            None => Some(self.dataset_mount.join(relpath)),
+        }
+    }
 }
+
+// key: snap mount, val: the dataset mount upon which the snap mount resides,
+// a reverse of map_of_snaps so we may look up any snap version by its ancestors
+static SNAP_MOUNT_INDEX: Lazy<HashMap<PathBuf, PathBuf>> = Lazy::new(|| {
+    GLOBAL_CONFIG
+        .dataset_collection
+        .map_of_snaps
+        .iter()
+        .flat_map(|(dataset_mount, snap_mounts)| {
+            snap_mounts
+                .iter()
+                .map(|snap_mount| (snap_mount.clone(), dataset_mount.clone()))
+        })
+        .collect()
+});
@@ -0,0 +1,175 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Deref;
+use std::process::Command as ExecProcess;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::config::generate::PrintMode;
+use crate::exec::purge::PurgeFiles;
+use crate::exec::snapshot::TakeSnapshot;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::display_human_size;
+use crate::lookup::snap_names::SnapNameMap;
+use crate::GLOBAL_CONFIG;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapSpace {
+    pub used: u64,
+    pub written: u64,
+    pub referenced: u64,
+}
+
+impl std::fmt::Display for SnapSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "used: {}, written: {}, referenced: {}",
+            display_human_size(self.used),
+            display_human_size(self.written),
+            display_human_size(self.referenced)
+        )
+    }
+}
+
+// key: full snapshot name, like "pool/dataset@snap_name"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapSpaceMap {
+    inner: BTreeMap<String, SnapSpace>,
+    // what destroying every snapshot listed would reclaim, which, unlike the sum of
+    // each snapshot's used space, includes data shared only among those snapshots
+    pub opt_reclaimable: Option<u64>,
+}
+
+impl Deref for SnapSpaceMap {
+    type Target = BTreeMap<String, SnapSpace>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl SnapSpaceMap {
+    pub fn new(snap_name_map: &SnapNameMap) -> HttmResult<Self> {
+        let zfs_command = TakeSnapshot::get_zfs_command()?;
+
+        // only ZFS snapshot names contain a '@', and a snapshot may contain more than one file
+        let snap_names: Vec<String> = snap_name_map
+            .values()
+            .flatten()
+            .filter(|snap_name| snap_name.contains('@'))
+            .cloned()
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+
+        if snap_names.is_empty() {
+            return Ok(Self {
+                inner: BTreeMap::new(),
+                opt_reclaimable: None,
+            });
+        }
+
+        let mut process_args = vec![
+            "list",
+            "-Hp",
+            "-t",
+            "snapshot",
+            "-o",
+            "name,used,written,referenced",
+        ];
+        process_args.extend(snap_names.iter().map(String::as_str));
+
+        let process_output = ExecProcess::new(&zfs_command)
+            .args(&process_args)
+            .output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+        if !stderr_string.is_empty() {
+            let msg = "httm was unable to determine the space used by snapshots. The 'zfs' command issued the following error: ".to_owned() + stderr_string;
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let inner = std::str::from_utf8(&process_output.stdout)?
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+
+                let name = fields.next()?;
+                let mut values = fields.map(|field| field.parse::<u64>().ok());
+
+                let snap_space = SnapSpace {
+                    used: values.next()??,
+                    written: values.next()??,
+                    referenced: values.next()??,
+                };
+
+                Some((name.to_owned(), snap_space))
+            })
+            .collect();
+
+        // an estimate requires the same privileges as the destroy itself, so we only warn here
+        let opt_reclaimable = match PurgeFiles::get_reclaim_estimate(&zfs_command, &snap_names) {
+            Ok(reclaimable) => Some(reclaimable),
+            Err(error) => {
+                eprintln!("WARNING: {error}");
+                None
+            }
+        };
+
+        Ok(Self {
+            inner,
+            opt_reclaimable,
+        })
+    }
+
+    pub fn get_reclaimable_summary(&self) -> Option<String> {
+        self.opt_reclaimable.map(|reclaimable| {
+            format!(
+                "Destroying all the snapshot/s listed would reclaim an estimated {}.",
+                display_human_size(reclaimable)
+            )
+        })
+    }
+}
+
+impl Serialize for SnapSpace {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("SnapSpace", 3)?;
+
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            state.serialize_field("used", &self.used)?;
+            state.serialize_field("written", &self.written)?;
+            state.serialize_field("referenced", &self.referenced)?;
+        } else {
+            state.serialize_field("used", &display_human_size(self.used))?;
+            state.serialize_field("written", &display_human_size(self.written))?;
+            state.serialize_field("referenced", &display_human_size(self.referenced))?;
+        }
+
+        state.end()
+    }
+}
@@ -22,17 +22,20 @@ use std::{
     ops::Deref,
     ops::DerefMut,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
 use rayon::prelude::*;
 
 use crate::library::results::{HttmError, HttmResult};
+use crate::lookup::deletion_window::SnapInstant;
+use crate::lookup::snap_names::DeconstructedSnapPathData;
 use crate::{
     config::generate::ListSnapsOfType,
     data::paths::{CompareVersionsContainer, PathData},
 };
 use crate::{
-    config::generate::{BulkExclusion, Config, LastSnapMode},
+    config::generate::{BulkExclusion, Config, LastSnapMode, VersionFilters, VersionSort},
     GLOBAL_CONFIG,
 };
 
@@ -93,10 +96,20 @@ impl VersionsMap {
             versions_map.omit_ditto()
         }
 
+        // filter before last snap, so last snap mode can find the last version before a date,
+        // but sort after, as last snap mode and omit ditto expect versions in modify time order
+        if let Some(version_filters) = &config.opt_version_filters {
+            versions_map.filter_versions(version_filters)
+        }
+
         if let Some(last_snap_mode) = &config.opt_last_snap {
             versions_map.get_last_snap(last_snap_mode)
         }
 
+        if let Some(version_filters) = &config.opt_version_filters {
+            versions_map.sort_versions(version_filters)
+        }
+
         Ok(versions_map)
     }
 
@@ -110,12 +123,18 @@ impl VersionsMap {
         let all_snap_versions: BTreeMap<PathData, Vec<PathData>> = path_set
             .par_iter()
             .map(|pathdata| {
-                let snaps: Vec<PathData> =
+                let mut snaps: Vec<PathData> =
                     Self::get_search_bundles(pathdata, snaps_selected_for_search)
                         .flat_map(|search_bundle| {
                             search_bundle.get_versions_processed(&config.uniqueness)
                         })
                         .collect();
+
+                // replicated snapshots may be found in more than one search bundle
+                if matches!(config.uniqueness, ListSnapsOfType::UniqueSnapTime) {
+                    snaps = Self::unique_by_snap_time(snaps);
+                }
+
                 (pathdata.clone(), snaps)
             })
             .collect();
@@ -125,6 +144,26 @@ impl VersionsMap {
         versions_map
     }
 
+    // versions without a snapshot time are never considered duplicates
+    fn unique_by_snap_time(snaps: Vec<PathData>) -> Vec<PathData> {
+        let mut timed_snaps: Vec<(Option<SystemTime>, PathData)> = snaps
+            .into_iter()
+            .map(|snap_version| {
+                let opt_snap_time =
+                    SnapInstant::from_version(&snap_version).map(|snap_instant| snap_instant.time);
+                (opt_snap_time, snap_version)
+            })
+            .collect();
+
+        timed_snaps.sort_by_key(|(opt_snap_time, _snap_version)| *opt_snap_time);
+        timed_snaps.dedup_by(|(a_time, _a), (b_time, _b)| a_time.is_some() && a_time == b_time);
+
+        timed_snaps
+            .into_iter()
+            .map(|(_opt_snap_time, snap_version)| snap_version)
+            .collect()
+    }
+
     pub fn get_search_bundles<'a>(
         pathdata: &'a PathData,
         snaps_selected_for_search: &'a [SnapDatasetType],
@@ -155,6 +194,55 @@ impl VersionsMap {
         });
     }
 
+    fn filter_versions(&mut self, version_filters: &VersionFilters) {
+        self.values_mut().for_each(|snaps| {
+            snaps.retain(|snap_version| {
+                let md = snap_version.get_md_infallible();
+
+                version_filters
+                    .opt_since
+                    .map_or(true, |since| md.modify_time >= since)
+                    && version_filters
+                        .opt_until
+                        .map_or(true, |until| md.modify_time <= until)
+                    && version_filters
+                        .opt_min_size
+                        .map_or(true, |min_size| md.size >= min_size)
+                    && version_filters
+                        .opt_max_size
+                        .map_or(true, |max_size| md.size <= max_size)
+            })
+        });
+    }
+
+    fn sort_versions(&mut self, version_filters: &VersionFilters) {
+        self.values_mut().for_each(|snaps| {
+            match version_filters.opt_sort {
+                // versions are already in modify time order
+                None | Some(VersionSort::ModifyTime) => {}
+                Some(VersionSort::Size) => snaps.sort_by_key(|snap_version| {
+                    let md = snap_version.get_md_infallible();
+                    (md.size, md.modify_time)
+                }),
+                Some(VersionSort::SnapshotDate) => snaps.sort_by_cached_key(|snap_version| {
+                    SnapInstant::from_version(snap_version).map(|snap_instant| snap_instant.time)
+                }),
+                Some(VersionSort::SnapshotName) => snaps.sort_by_cached_key(|snap_version| {
+                    DeconstructedSnapPathData::from_snap_mount(snap_version)
+                        .map(|deconstructed| deconstructed.snap_name)
+                }),
+            }
+
+            if version_filters.reverse {
+                snaps.reverse()
+            }
+
+            if let Some(limit) = version_filters.opt_limit {
+                snaps.truncate(limit)
+            }
+        });
+    }
+
     fn get_last_snap(&mut self, last_snap_mode: &LastSnapMode) {
         self.iter_mut().for_each(|(pathdata, snaps)| {
             *snaps = match snaps.last() {
@@ -393,7 +481,8 @@ impl<'a> RelativePathAndSnapMounts<'a> {
         let all_versions = self.get_versions_unprocessed();
 
         let sorted_versions: Vec<PathData> = match uniqueness {
-            ListSnapsOfType::All => {
+            // unique snap time versions are processed across all search bundles, in generate_map
+            ListSnapsOfType::All | ListSnapsOfType::UniqueSnapTime => {
                 let mut versions: Vec<PathData> = all_versions.collect();
 
                 versions.sort_unstable_by(|a, b| {
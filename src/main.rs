@@ -21,37 +21,59 @@ mod data {
     pub mod selection;
 }
 mod display_map {
+    pub mod delimited;
     pub mod format;
     pub mod helper;
 }
 mod display_versions {
     pub mod format;
     pub mod num_versions;
+    pub mod template;
+    pub mod timeline;
     pub mod wrapper;
 }
 mod exec {
+    pub mod changelog;
     pub mod deleted;
+    pub mod diff_versions;
+    pub mod dir_summary;
+    pub mod export_git;
+    pub mod find;
+    pub mod grep;
+    pub mod hold;
     pub mod interactive;
+    pub mod ounce;
     pub mod preview;
+    pub mod prune;
     pub mod purge;
+    pub mod recover_deleted;
     pub mod recursive;
     pub mod roll_forward;
     pub mod snapshot;
+    pub mod tree_diff;
+    pub mod watch;
 }
 mod config {
     pub mod generate;
     pub mod install_hot_keys;
 }
 mod library {
+    pub mod audit;
     pub mod diff_copy;
+    pub mod git_objects;
+    pub mod hooks;
     pub mod iter_extensions;
+    pub mod line_diff;
     pub mod results;
     pub mod utility;
 }
 mod lookup {
     pub mod deleted;
+    pub mod deletion_window;
+    pub mod dir_contents;
     pub mod file_mounts;
     pub mod snap_names;
+    pub mod snap_space;
     pub mod versions;
 }
 mod parse {
@@ -61,10 +83,23 @@ mod parse {
     pub mod snaps;
 }
 
+use crate::display_map::delimited::PrintAsDelimited;
 use crate::display_map::helper::PrintAsMap;
+use exec::changelog::Changelog;
+use exec::diff_versions::DiffVersions;
+use exec::dir_summary::DirSummary;
+use exec::export_git::ExportGit;
+use exec::find::FindVersions;
+use exec::grep::GrepVersions;
+use exec::hold::HoldSnapshots;
+use exec::ounce::Ounce;
+use exec::prune::PruneSnapshots;
 use exec::purge::PurgeFiles;
+use exec::recover_deleted::RecoverDeleted;
 use exec::roll_forward::RollForward;
 use exec::snapshot::TakeSnapshot;
+use exec::tree_diff::TreeDiff;
+use exec::watch::WatchPaths;
 use library::utility::print_output_buf;
 use once_cell::sync::Lazy;
 
@@ -76,6 +111,7 @@ use crate::exec::interactive::InteractiveBrowse;
 use crate::exec::recursive::NonInteractiveRecursiveWrapper;
 use crate::library::results::HttmResult;
 use crate::lookup::snap_names::SnapNameMap;
+use crate::lookup::snap_space::SnapSpaceMap;
 use crate::lookup::versions::VersionsMap;
 
 pub const ZFS_HIDDEN_DIRECTORY: &str = ".zfs";
@@ -127,26 +163,62 @@ fn exec() -> HttmResult<()> {
         // ExecMode::NonInteractiveRecursive, ExecMode::SnapFileMount, and ExecMode::MountsForFiles will print their
         // output elsewhere
         ExecMode::NonInteractiveRecursive(_) => NonInteractiveRecursiveWrapper::exec(),
-        ExecMode::SnapFileMount(snapshot_suffix) => TakeSnapshot::exec(snapshot_suffix),
+        ExecMode::SnapFileMount(snapshot_options) => TakeSnapshot::exec(snapshot_options),
         ExecMode::SnapsForFiles(opt_filters) => {
             let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
-            let snap_name_map = SnapNameMap::exec(versions_map, opt_filters);
-            let printable_map = PrintAsMap::from(&snap_name_map);
-            let output_buf = printable_map.to_string();
+            let output_buf = if GLOBAL_CONFIG.opt_delimited_format.is_some() {
+                PrintAsDelimited::from_snap_versions(&versions_map, opt_filters).to_string()
+            } else {
+                let snap_name_map = SnapNameMap::exec(&versions_map, opt_filters);
+
+                if matches!(opt_filters, Some(filters) if filters.with_space) {
+                    let snap_space = SnapSpaceMap::new(&snap_name_map)?;
+                    PrintAsMap::from(&snap_name_map)
+                        .with_snap_space(snap_space)
+                        .to_string()
+                } else {
+                    PrintAsMap::from(&snap_name_map).to_string()
+                }
+            };
 
             print_output_buf(output_buf)
         }
-        ExecMode::Purge(opt_filters) => {
+        ExecMode::Purge(opt_filters, consent) => {
+            let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
+            PurgeFiles::exec(versions_map, opt_filters, consent)
+        }
+        ExecMode::Hold(opt_filters, hold_action) => {
             let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
-            PurgeFiles::exec(versions_map, opt_filters)
+            HoldSnapshots::exec(versions_map, opt_filters, hold_action)
         }
         ExecMode::MountsForFiles(mount_display) => {
             let mounts_map = &MountsForFiles::new(mount_display);
-            let printable_map: PrintAsMap = mounts_map.into();
-            let output_buf = printable_map.to_string();
+            let output_buf = if GLOBAL_CONFIG.opt_delimited_format.is_some() {
+                PrintAsDelimited::from(mounts_map).to_string()
+            } else {
+                let printable_map: PrintAsMap = mounts_map.into();
+                printable_map.to_string()
+            };
 
             print_output_buf(output_buf)
         }
         ExecMode::RollForward(snap_name) => RollForward::exec(snap_name),
+        ExecMode::Diff(diff_mode) => DiffVersions::exec(diff_mode),
+        ExecMode::ExportGit(dest) => ExportGit::exec(dest),
+        ExecMode::Ounce(ounce_options) => Ounce::exec(ounce_options),
+        ExecMode::Watch(watch_options) => WatchPaths::exec(watch_options),
+        ExecMode::Prune(prune_options) => PruneSnapshots::exec(prune_options),
+        ExecMode::RecoverDeleted(recover_options) => RecoverDeleted::exec(recover_options),
+        ExecMode::Changelog => {
+            let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
+            Changelog::exec(versions_map)
+        }
+        ExecMode::TreeDiff(tree_diff_options) => TreeDiff::exec(tree_diff_options),
+        ExecMode::Grep(regex) => GrepVersions::exec(regex),
+        ExecMode::Find(find_options) => FindVersions::exec(find_options),
+        ExecMode::DirSummary => {
+            let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
+            DirSummary::exec(versions_map)
+        }
     }
 }
@@ -29,6 +29,18 @@ pub enum FilesystemType {
     Nilfs2,
 }
 
+impl std::fmt::Display for FilesystemType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fs_type = match self {
+            FilesystemType::Zfs => "zfs",
+            FilesystemType::Btrfs => "btrfs",
+            FilesystemType::Nilfs2 => "nilfs2",
+        };
+
+        write!(f, "{fs_type}")
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RemotePathAndFsType {
     pub remote_dir: PathBuf,